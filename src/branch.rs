@@ -0,0 +1,177 @@
+use crate::{
+    commit::Footer,
+    config::{BranchPattern, Config, Pattern},
+    git,
+};
+
+/// A ticket reference found in a branch name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ticket {
+    pub branch: String,
+    /// The footer to add, such as `Refs: PROJ-1234`.
+    pub footer: Footer,
+    pub scope: Option<String>,
+}
+
+/// Standards whose names look like Jira keys, as in `feat/UTF-8-support`
+/// or `fix/ISO-8601-dates`. A `ticket` group never matches these.
+const STANDARDS: [&str; 10] = [
+    "AES", "CVE", "ECMA", "ES", "IEEE", "ISO", "MD", "RFC", "SHA", "UTF",
+];
+
+/// The patterns convit ships with: Jira-style keys such as `PROJ-1234`
+/// and GitHub issue numbers such as `gh-87`, fixed on `fix/` branches and
+/// referenced on any other.
+pub fn default_patterns() -> Vec<BranchPattern> {
+    const KEY: &str = r"(?P<ticket>[A-Z]{2}[A-Z0-9]*-[0-9]+)(?:[-_/.]|$)";
+    const ISSUE: &str = r"(?:gh-|issue-|#)(?P<number>[0-9]+)(?:[-_/.]|$)";
+    const FIX: &str = r"^(?:bug|hot)?fix(?:es)?/";
+    let pattern = |pattern: String, reference: Option<&str>, footer: &str| BranchPattern {
+        pattern: Pattern::new(&pattern).expect("built-in branch patterns are valid"),
+        reference: reference.map(str::to_string),
+        footer: footer.to_string(),
+    };
+    vec![
+        pattern(format!(r"{FIX}(?:.*[-_/])?{KEY}"), None, "Fixes"),
+        pattern(
+            format!(r"{FIX}(?:.*[-_/])?{ISSUE}"),
+            Some("#${number}"),
+            "Fixes",
+        ),
+        pattern(
+            format!(r"{FIX}(?P<number>[0-9]+)(?:[-_/.]|$)"),
+            Some("#${number}"),
+            "Fixes",
+        ),
+        pattern(format!(r"(?:^|[-_/]){KEY}"), None, "Refs"),
+        pattern(format!(r"(?:^|[-_/]){ISSUE}"), Some("#${number}"), "Refs"),
+    ]
+}
+
+/// The ticket in `branch`, from the first configured pattern that finds
+/// one. Scopes that aren't allowed are left out.
+pub fn ticket(branch: &str, config: &Config) -> Option<Ticket> {
+    config.branch_patterns.iter().find_map(|pattern| {
+        let regex = pattern.pattern.regex();
+        let mut start = 0;
+        let captures = loop {
+            let captures = regex.captures_at(branch, start)?;
+            match captures.name("ticket") {
+                // Look for another match from the next character on.
+                Some(ticket) if is_standard(ticket.as_str()) => {
+                    let found = captures.get(0)?.start();
+                    start = found + branch[found..].chars().next().map_or(1, char::len_utf8);
+                }
+                _ => break captures,
+            }
+        };
+        let reference = match &pattern.reference {
+            Some(template) => {
+                let mut reference = String::new();
+                captures.expand(template, &mut reference);
+                reference
+            }
+            None => captures
+                .name("ticket")
+                .or_else(|| captures.get(0))?
+                .as_str()
+                .to_string(),
+        };
+        let reference = reference.trim();
+        if reference.is_empty() {
+            return None;
+        }
+        let scope = captures
+            .name("scope")
+            .map(|scope| scope.as_str().to_string())
+            .filter(|scope| !scope.is_empty())
+            .filter(|scope| config.scopes.is_empty() || config.scopes.contains(scope));
+        Some(Ticket {
+            branch: branch.to_string(),
+            footer: Footer::new(pattern.footer.as_str(), reference),
+            scope,
+        })
+    })
+}
+
+fn is_standard(ticket: &str) -> bool {
+    ticket
+        .split_once('-')
+        .is_some_and(|(key, _)| STANDARDS.contains(&key))
+}
+
+/// The ticket in the current branch's name, if any.
+pub fn current(config: &Config) -> Option<Ticket> {
+    ticket(&git::current_branch()?, config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            branch_patterns: default_patterns(),
+            ..Config::default()
+        }
+    }
+
+    fn footer(branch: &str) -> Option<String> {
+        ticket(branch, &config()).map(|ticket| ticket.footer.to_string())
+    }
+
+    #[test]
+    fn finds_jira_keys() {
+        assert_eq!(
+            footer("feature/PROJ-1234-add-login").as_deref(),
+            Some("Refs: PROJ-1234")
+        );
+        assert_eq!(footer("PROJ-7").as_deref(), Some("Refs: PROJ-7"));
+        assert_eq!(footer("fix/AB2-9_crash").as_deref(), Some("Fixes: AB2-9"));
+        assert_eq!(footer("feat/a-PROJ-12x"), None);
+        assert_eq!(footer("feat/X-12"), None);
+    }
+
+    #[test]
+    fn ignores_standards_that_look_like_keys() {
+        assert_eq!(footer("feat/UTF-8-support"), None);
+        assert_eq!(footer("chore/SHA-256"), None);
+        assert_eq!(footer("fix/ISO-8601-dates"), None);
+        assert_eq!(
+            footer("feat/UTF-8-PROJ-12").as_deref(),
+            Some("Refs: PROJ-12")
+        );
+    }
+
+    #[test]
+    fn finds_issue_numbers() {
+        assert_eq!(footer("fix/gh-87").as_deref(), Some("Fixes: #87"));
+        assert_eq!(footer("hotfix/123-crash").as_deref(), Some("Fixes: #123"));
+        assert_eq!(footer("bugfixes/issue-4.x").as_deref(), Some("Fixes: #4"));
+        assert_eq!(footer("feat/#5-menu").as_deref(), Some("Refs: #5"));
+        assert_eq!(footer("feat/v2-87"), None);
+        assert_eq!(footer("main"), None);
+    }
+
+    #[test]
+    fn uses_configured_references_and_scopes() {
+        let mut config = config();
+        config
+            .merge_json(
+                r#"{
+                    "scopes": ["api"],
+                    "branch_patterns": [{
+                        "pattern": "^(?P<scope>[a-z]+)/(?P<n>[0-9]+)",
+                        "reference": "ACME-${n}",
+                        "footer": "Jira"
+                    }]
+                }"#,
+            )
+            .unwrap();
+        let ticket = ticket("api/42", &config).unwrap();
+        assert_eq!(ticket.footer, Footer::new("Jira", "ACME-42"));
+        assert_eq!(ticket.scope.as_deref(), Some("api"));
+        // Scopes that aren't allowed are left out.
+        assert_eq!(super::ticket("web/42", &config).unwrap().scope, None);
+    }
+}
@@ -0,0 +1,296 @@
+use std::fmt::Write;
+
+use crate::{
+    commit::ConventionalCommit,
+    config::Config,
+    git::{self, GitError, LogEntry},
+};
+
+/// Section headings for the types convit ships with, in the order they
+/// are listed. Other types follow, headed by their name.
+const HEADINGS: [(&str, &str); 11] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("revert", "Reverts"),
+    ("refactor", "Code Refactoring"),
+    ("docs", "Documentation"),
+    ("style", "Styles"),
+    ("test", "Tests"),
+    ("build", "Build System"),
+    ("ci", "Continuous Integration"),
+    ("chore", "Chores"),
+];
+
+/// The heading for a commit type's section.
+pub fn heading(commit_type: &str) -> &str {
+    HEADINGS
+        .iter()
+        .find(|(name, _)| *name == commit_type)
+        .map_or(commit_type, |(_, heading)| heading)
+}
+
+/// Commits between two refs, parsed and grouped for a changelog.
+#[derive(Debug, Clone, Default)]
+pub struct Changelog {
+    /// Conventional commits whose type is configured, newest first.
+    pub commits: Vec<(LogEntry, ConventionalCommit)>,
+    /// Commits that aren't conventional or have an unknown type.
+    pub other: Vec<LogEntry>,
+}
+
+impl Changelog {
+    /// Sorts log entries into conventional commits of configured types and
+    /// everything else.
+    pub fn new(entries: Vec<LogEntry>, config: &Config) -> Self {
+        let mut changelog = Changelog::default();
+        for entry in entries {
+            let commit = entry
+                .message
+                .parse::<ConventionalCommit>()
+                .map(|mut commit| {
+                    config.resolve_gitmoji(&mut commit);
+                    commit
+                });
+            match commit {
+                Ok(commit) if config.types.iter().any(|t| t.name == commit.commit_type) => {
+                    changelog.commits.push((entry, commit));
+                }
+                _ => changelog.other.push(entry),
+            }
+        }
+        changelog
+    }
+
+    /// Keeps the commits `keep` accepts. Commits that aren't conventional
+    /// are passed without a parsed commit.
+    pub fn retain(&mut self, keep: impl Fn(&LogEntry, Option<&ConventionalCommit>) -> bool) {
+        self.commits
+            .retain(|(entry, commit)| keep(entry, Some(commit)));
+        self.other.retain(|entry| keep(entry, None));
+    }
+
+    /// Reads the commits in `from..to`. Without `from`, everything since the
+    /// last tag before `to` is read, or the whole history if there is none.
+    pub fn from_git(from: Option<&str>, to: &str, config: &Config) -> Result<Self, GitError> {
+        let from = match from {
+            Some(from) => Some(from.to_string()),
+            None if git::is_tag(to) => git::latest_tag(&format!("{to}^")),
+            None => git::latest_tag(to),
+        };
+        let range = match from {
+            Some(from) => format!("{from}..{to}"),
+            None => to.to_string(),
+        };
+        Ok(Changelog::new(git::log(&range)?, config))
+    }
+
+    /// Renders a Keep a Changelog section with a heading per configured
+    /// type; commits that aren't conventional are listed under
+    /// "Other" when `include_other` is set.
+    pub fn render(
+        &self,
+        title: &str,
+        date: Option<&str>,
+        config: &Config,
+        include_other: bool,
+    ) -> String {
+        let mut out = match date {
+            Some(date) => format!("## [{title}] - {date}\n"),
+            None => format!("## [{title}]\n"),
+        };
+
+        let breaking: Vec<String> = self
+            .commits
+            .iter()
+            .filter(|(_, commit)| commit.is_breaking())
+            .map(|(entry, commit)| {
+                // Prefer the footer's explanation over the description.
+                let explanation = commit
+                    .footers
+                    .iter()
+                    .find(|footer| footer.is_breaking_change())
+                    .map_or(commit.description.as_str(), |footer| footer.value.as_str());
+                item(commit.scope.as_deref(), explanation, &entry.short_hash)
+            })
+            .collect();
+        section(&mut out, "BREAKING CHANGES", &breaking);
+
+        // Known types come first, in the order of `HEADINGS`.
+        let mut types: Vec<&str> = config.types.iter().map(|t| t.name.as_str()).collect();
+        types.sort_by_key(|name| {
+            HEADINGS
+                .iter()
+                .position(|(known, _)| known == name)
+                .unwrap_or(HEADINGS.len())
+        });
+        for commit_type in types {
+            let items: Vec<String> = self
+                .commits
+                .iter()
+                .filter(|(_, commit)| commit.commit_type == commit_type)
+                .map(|(entry, commit)| {
+                    item(
+                        commit.scope.as_deref(),
+                        &commit.description,
+                        &entry.short_hash,
+                    )
+                })
+                .collect();
+            section(&mut out, heading(commit_type), &items);
+        }
+
+        if include_other {
+            let items: Vec<String> = self
+                .other
+                .iter()
+                .map(|entry| {
+                    let summary = entry.message.lines().next().unwrap_or_default();
+                    item(None, summary, &entry.short_hash)
+                })
+                .collect();
+            section(&mut out, "Other", &items);
+        }
+        out
+    }
+}
+
+fn item(scope: Option<&str>, text: &str, short_hash: &str) -> String {
+    // Continuation lines of multi-line footers are indented under the bullet.
+    let text = text.lines().collect::<Vec<_>>().join("\n  ");
+    match scope {
+        Some(scope) => format!("- **{scope}:** {text} ({short_hash})"),
+        None => format!("- {text} ({short_hash})"),
+    }
+}
+
+fn section(out: &mut String, heading: &str, items: &[String]) {
+    if items.is_empty() {
+        return;
+    }
+    let _ = write!(out, "\n### {heading}\n\n");
+    for item in items {
+        out.push_str(item);
+        out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config
+            .merge_json(
+                r#"{"types": [
+                    {"name": "deps"},
+                    {"name": "docs"},
+                    {"name": "fix"},
+                    {"name": "feat"},
+                    {"name": "chore"}
+                ]}"#,
+            )
+            .unwrap();
+        config
+    }
+
+    fn changelog() -> Changelog {
+        let entries = [
+            ("h1", "feat(ui): add dark mode"),
+            ("h2", "fix!: drop the v1 API"),
+            ("h3", "docs: explain setup\n\nLonger text."),
+            ("h4", "deps: bump serde"),
+            ("h5", "Update README\n\nBy hand."),
+            ("h6", "wip: stuff"),
+            (
+                "h7",
+                "feat: new parser\n\nBREAKING CHANGE: config moved\nto toml",
+            ),
+        ]
+        .into_iter()
+        .map(|(hash, message)| LogEntry {
+            hash: hash.to_string(),
+            short_hash: hash.to_string(),
+            message: message.to_string(),
+            paths: Vec::new(),
+        })
+        .collect();
+        Changelog::new(entries, &config())
+    }
+
+    const SECTIONS: &str = "## [1.0.0] - 2024-05-01
+
+### BREAKING CHANGES
+
+- drop the v1 API (h2)
+- config moved
+  to toml (h7)
+
+### Features
+
+- **ui:** add dark mode (h1)
+- new parser (h7)
+
+### Bug Fixes
+
+- drop the v1 API (h2)
+
+### Documentation
+
+- explain setup (h3)
+
+### deps
+
+- bump serde (h4)
+";
+
+    #[test]
+    fn sorts_out_commits_of_other_types() {
+        let changelog = changelog();
+        let hashes = |entries: Vec<&LogEntry>| -> Vec<String> {
+            entries.iter().map(|entry| entry.hash.clone()).collect()
+        };
+        assert_eq!(
+            hashes(changelog.commits.iter().map(|(entry, _)| entry).collect()),
+            ["h1", "h2", "h3", "h4", "h7"]
+        );
+        assert_eq!(hashes(changelog.other.iter().collect()), ["h5", "h6"]);
+    }
+
+    #[test]
+    fn renders_sections_in_heading_order() {
+        let changelog = changelog();
+        assert_eq!(
+            changelog.render("1.0.0", Some("2024-05-01"), &config(), false),
+            SECTIONS
+        );
+        assert_eq!(
+            changelog.render("1.0.0", Some("2024-05-01"), &config(), true),
+            format!("{SECTIONS}\n### Other\n\n- Update README (h5)\n- wip: stuff (h6)\n")
+        );
+        assert_eq!(
+            Changelog::default().render("Unreleased", None, &config(), true),
+            "## [Unreleased]\n"
+        );
+    }
+
+    #[test]
+    fn retains_commits_by_entry_and_message() {
+        let mut changelog = changelog();
+        changelog.retain(|entry, commit| {
+            commit.is_some_and(|commit| commit.commit_type == "feat") || entry.hash == "h5"
+        });
+        let rendered = changelog.render("1.1.0", None, &config(), true);
+        assert!(rendered.starts_with("## [1.1.0]\n\n### BREAKING CHANGES\n\n- config moved"));
+        assert!(!rendered.contains("(h2)"));
+        assert!(rendered.ends_with("### Other\n\n- Update README (h5)\n"));
+    }
+
+    #[test]
+    fn headings_fall_back_to_the_type() {
+        assert_eq!(heading("feat"), "Features");
+        assert_eq!(heading("ci"), "Continuous Integration");
+        assert_eq!(heading("deps"), "deps");
+    }
+}
@@ -0,0 +1,495 @@
+use std::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::gitmoji::{self, Position};
+
+/// Whether a commit message is complete enough to be committed.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum CommitStatus {
+    Ready,
+    #[default]
+    Unready,
+}
+
+/// The separator between a footer token and its value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Separator {
+    /// `Token: value`
+    Colon,
+    /// `Token #value`
+    Hash,
+}
+
+/// A single `Token: value` or `Token #value` trailer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Footer {
+    pub token: String,
+    pub separator: Separator,
+    pub value: String,
+}
+
+impl Footer {
+    pub fn new(token: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+            separator: Separator::Colon,
+            value: value.into(),
+        }
+    }
+
+    /// `BREAKING CHANGE` and its `BREAKING-CHANGE` synonym.
+    pub fn is_breaking_change(&self) -> bool {
+        self.token == "BREAKING CHANGE" || self.token == "BREAKING-CHANGE"
+    }
+
+    /// Parses the first line of a footer, returning `None` if the line
+    /// doesn't start with a valid token and separator.
+    pub fn parse_line(line: &str) -> Option<Footer> {
+        let (token, rest) = ["BREAKING CHANGE", "BREAKING-CHANGE"]
+            .iter()
+            .find_map(|token| line.strip_prefix(token).map(|rest| (*token, rest)))
+            .or_else(|| {
+                let end = line.find(|c: char| !(c.is_alphanumeric() || c == '-'))?;
+                Some(line.split_at(end))
+            })?;
+
+        if token.is_empty() {
+            return None;
+        }
+
+        let (separator, value) = if let Some(value) = rest.strip_prefix(": ") {
+            (Separator::Colon, value)
+        } else if let Some(value) = rest.strip_prefix(" #") {
+            (Separator::Hash, value)
+        } else {
+            return None;
+        };
+
+        if value.trim().is_empty() {
+            return None;
+        }
+
+        Some(Footer {
+            token: token.to_string(),
+            separator,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl fmt::Display for Footer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.separator {
+            Separator::Colon => write!(f, "{}: {}", self.token, self.value),
+            Separator::Hash => write!(f, "{} #{}", self.token, self.value),
+        }
+    }
+}
+
+/// A gitmoji at the start of a header, such as `✨` or `:sparkles:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderGitmoji {
+    pub text: String,
+    pub position: Position,
+}
+
+/// An owned commit message following the Conventional Commits specification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub gitmoji: Option<HeaderGitmoji>,
+    /// Empty after parsing a header whose gitmoji replaces the type, until
+    /// `Config::resolve_gitmoji` fills it in.
+    pub commit_type: String,
+    pub scope: Option<String>,
+    /// Set by the `!` marker in the header.
+    pub breaking: bool,
+    pub description: String,
+    pub body: Option<String>,
+    pub footers: Vec<Footer>,
+    pub commit_status: CommitStatus,
+}
+
+impl ConventionalCommit {
+    pub fn new() -> Self {
+        ConventionalCommit::default()
+    }
+
+    /// The first line of the message: `type(scope)!: description`, with
+    /// the gitmoji before the type or in its place.
+    pub fn header(&self) -> String {
+        let mut header = match &self.gitmoji {
+            Some(gitmoji) if gitmoji.position == Position::Replace => gitmoji.text.clone(),
+            Some(gitmoji) => format!("{} {}", gitmoji.text, self.commit_type),
+            None => self.commit_type.clone(),
+        };
+        if let Some(scope) = &self.scope {
+            header.push_str(&format!("({scope})"));
+        }
+        if self.breaking {
+            header.push('!');
+        }
+        header.push_str(": ");
+        header.push_str(&self.description);
+        header
+    }
+
+    /// True if the header has the `!` marker or any footer is a breaking change.
+    pub fn is_breaking(&self) -> bool {
+        self.breaking || self.footers.iter().any(Footer::is_breaking_change)
+    }
+}
+
+impl fmt::Display for ConventionalCommit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.header())?;
+        if let Some(body) = &self.body {
+            write!(f, "\n\n{body}")?;
+        }
+        if !self.footers.is_empty() {
+            f.write_str("\n")?;
+            for footer in &self.footers {
+                write!(f, "\n{footer}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors found while parsing the header of a commit message.
+///
+/// Columns are 1-based and counted in characters.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseError {
+    #[error("commit message is empty")]
+    Empty,
+    #[error("missing commit type at column {column}")]
+    MissingType { column: usize },
+    #[error("unexpected character {found:?} in commit type at column {column}")]
+    InvalidType { found: char, column: usize },
+    #[error("unclosed scope, expected `)` at column {column}")]
+    UnclosedScope { column: usize },
+    #[error("empty scope at column {column}")]
+    EmptyScope { column: usize },
+    #[error("expected `: ` after the commit type at column {column}")]
+    MissingSeparator { column: usize },
+    #[error("missing description at column {column}")]
+    MissingDescription { column: usize },
+}
+
+impl ParseError {
+    /// The column the error points at. Header errors are always on the first line.
+    pub fn column(&self) -> usize {
+        match *self {
+            ParseError::Empty => 1,
+            ParseError::MissingType { column }
+            | ParseError::InvalidType { column, .. }
+            | ParseError::UnclosedScope { column }
+            | ParseError::EmptyScope { column }
+            | ParseError::MissingSeparator { column }
+            | ParseError::MissingDescription { column } => column,
+        }
+    }
+}
+
+impl FromStr for ConventionalCommit {
+    type Err = ParseError;
+
+    fn from_str(message: &str) -> Result<Self, Self::Err> {
+        let mut lines = message
+            .trim_end()
+            .lines()
+            .skip_while(|l| l.trim().is_empty());
+        let header = lines.next().ok_or(ParseError::Empty)?;
+        let mut commit = parse_header(header)?;
+
+        let rest: Vec<&str> = lines.map(str::trim_end).collect();
+        let footer_start = footer_block_start(&rest);
+
+        let body = rest[..footer_start]
+            .iter()
+            .skip_while(|l| l.is_empty())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("\n");
+        let body = body.trim_end();
+        if !body.is_empty() {
+            commit.body = Some(body.to_string());
+        }
+
+        for line in &rest[footer_start..] {
+            match Footer::parse_line(line) {
+                Some(footer) => commit.footers.push(footer),
+                // Anything that isn't a new token continues the previous value.
+                None => {
+                    if let Some(last) = commit.footers.last_mut() {
+                        last.value.push('\n');
+                        last.value.push_str(line);
+                    }
+                }
+            }
+        }
+
+        Ok(commit)
+    }
+}
+
+fn parse_header(header: &str) -> Result<ConventionalCommit, ParseError> {
+    let chars: Vec<char> = header.chars().collect();
+    let mut pos = 0;
+
+    // A gitmoji followed by a space comes before the type; otherwise it
+    // takes the type's place.
+    let mut gitmoji = gitmoji::prefix(header).map(|text| {
+        pos = text.chars().count();
+        let position = if chars.get(pos).is_some_and(|c| c.is_whitespace()) {
+            Position::Before
+        } else {
+            Position::Replace
+        };
+        HeaderGitmoji {
+            text: text.to_string(),
+            position,
+        }
+    });
+    let replaced = gitmoji
+        .as_ref()
+        .is_some_and(|gitmoji| gitmoji.position == Position::Replace);
+    while chars.get(pos).is_some_and(|c| c.is_whitespace()) {
+        pos += 1;
+    }
+
+    let start = pos;
+    while pos < chars.len()
+        && (chars[pos].is_alphanumeric() || chars[pos] == '-' || chars[pos] == '_')
+    {
+        pos += 1;
+    }
+    if pos == start && !replaced {
+        return Err(match chars.get(start) {
+            Some(&found) if !matches!(found, '(' | '!' | ':') => ParseError::InvalidType {
+                found,
+                column: start + 1,
+            },
+            _ => ParseError::MissingType { column: start + 1 },
+        });
+    }
+    let commit_type: String = chars[start..pos].iter().collect();
+    if let Some(gitmoji) = gitmoji.as_mut().filter(|_| !commit_type.is_empty()) {
+        // `✨feat: ...` still names its type.
+        gitmoji.position = Position::Before;
+    }
+
+    let mut scope = None;
+    if chars.get(pos) == Some(&'(') {
+        let start = pos + 1;
+        let end = chars[start..]
+            .iter()
+            .position(|&c| c == ')')
+            .map(|offset| start + offset)
+            .ok_or(ParseError::UnclosedScope {
+                column: chars.len() + 1,
+            })?;
+        let text: String = chars[start..end].iter().collect();
+        if text.trim().is_empty() {
+            return Err(ParseError::EmptyScope { column: start + 1 });
+        }
+        scope = Some(text);
+        pos = end + 1;
+    }
+
+    let breaking = chars.get(pos) == Some(&'!');
+    if breaking {
+        pos += 1;
+    }
+
+    match (chars.get(pos), chars.get(pos + 1)) {
+        (Some(':'), Some(' ')) => pos += 2,
+        (Some(':'), None) => return Err(ParseError::MissingDescription { column: pos + 2 }),
+        (Some(':'), _) => return Err(ParseError::MissingSeparator { column: pos + 2 }),
+        (Some(&found), _) if scope.is_none() && !breaking && !found.is_whitespace() => {
+            return Err(ParseError::InvalidType {
+                found,
+                column: pos + 1,
+            })
+        }
+        _ => return Err(ParseError::MissingSeparator { column: pos + 1 }),
+    }
+
+    let description: String = chars[pos..].iter().collect();
+    let description = description.trim();
+    if description.is_empty() {
+        return Err(ParseError::MissingDescription { column: pos + 1 });
+    }
+
+    Ok(ConventionalCommit {
+        gitmoji,
+        commit_type,
+        scope,
+        breaking,
+        description: description.to_string(),
+        ..ConventionalCommit::default()
+    })
+}
+
+/// Index of the first line of the trailing footer paragraph, or `lines.len()`
+/// if the message has no footers. `lines` are the lines after the header.
+pub fn footer_block_start(lines: &[&str]) -> usize {
+    let paragraph_start = lines
+        .iter()
+        .rposition(|l| l.is_empty())
+        .map_or(0, |i| i + 1);
+
+    match lines.get(paragraph_start) {
+        Some(line) if Footer::parse_line(line).is_some() => paragraph_start,
+        _ => lines.len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_header_body_and_footers() {
+        let message = "feat(parser)!: add arrays\n\nLonger text.\n\nSecond paragraph.\n\nRefs #12\nReviewed-by: A <a@b>\n";
+        let commit: ConventionalCommit = message.parse().unwrap();
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope.as_deref(), Some("parser"));
+        assert!(commit.breaking);
+        assert_eq!(commit.description, "add arrays");
+        assert_eq!(
+            commit.body.as_deref(),
+            Some("Longer text.\n\nSecond paragraph.")
+        );
+        assert_eq!(
+            commit.footers,
+            vec![
+                Footer {
+                    token: "Refs".to_string(),
+                    separator: Separator::Hash,
+                    value: "12".to_string(),
+                },
+                Footer::new("Reviewed-by", "A <a@b>"),
+            ]
+        );
+    }
+
+    #[test]
+    fn round_trips_through_display() {
+        for message in [
+            "fix: handle empty input",
+            "feat(ui): add a preview\n\nThe preview follows the selections.",
+            "refactor!: drop the old parser\n\nBREAKING CHANGE: `parse_v1` is gone\nRefs #3",
+            "docs(readme): explain footers\n\nFirst.\n\nSecond.\n\nSigned-off-by: A <a@b>",
+        ] {
+            let commit: ConventionalCommit = message.parse().unwrap();
+            assert_eq!(commit.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn continues_multiline_footer_values() {
+        let commit: ConventionalCommit = "fix: x\n\nBREAKING CHANGE: one\n  two\nRefs: #1"
+            .parse()
+            .unwrap();
+        assert_eq!(commit.footers[0].value, "one\n  two");
+        assert!(commit.is_breaking());
+        assert_eq!(commit.footers[1], Footer::new("Refs", "#1"));
+    }
+
+    #[test]
+    fn last_paragraph_without_a_footer_is_body() {
+        let commit: ConventionalCommit = "fix: x\n\nJust text.\nNo trailers here.".parse().unwrap();
+        assert_eq!(
+            commit.body.as_deref(),
+            Some("Just text.\nNo trailers here.")
+        );
+        assert!(commit.footers.is_empty());
+    }
+
+    #[test]
+    fn reports_header_errors_with_columns() {
+        let error = |header: &str| header.parse::<ConventionalCommit>().unwrap_err();
+        assert_eq!(error(""), ParseError::Empty);
+        assert_eq!(error(": x"), ParseError::MissingType { column: 1 });
+        assert_eq!(
+            error("fe at: x"),
+            ParseError::MissingSeparator { column: 3 }
+        );
+        assert_eq!(
+            error("fe.at: x"),
+            ParseError::InvalidType {
+                found: '.',
+                column: 3
+            }
+        );
+        assert_eq!(
+            error("feat(ui: x"),
+            ParseError::UnclosedScope { column: 11 }
+        );
+        assert_eq!(error("feat(): x"), ParseError::EmptyScope { column: 6 });
+        assert_eq!(error("feat:x"), ParseError::MissingSeparator { column: 6 });
+        assert_eq!(error("feat:"), ParseError::MissingDescription { column: 6 });
+        assert_eq!(
+            error("feat:  "),
+            ParseError::MissingDescription { column: 6 }
+        );
+        assert_eq!(
+            error("feat(ü)x"),
+            ParseError::MissingSeparator { column: 8 }
+        );
+    }
+
+    #[test]
+    fn parses_footer_lines() {
+        assert_eq!(
+            Footer::parse_line("BREAKING CHANGE: gone"),
+            Some(Footer::new("BREAKING CHANGE", "gone"))
+        );
+        assert_eq!(Footer::parse_line("Not a footer"), None);
+        assert_eq!(Footer::parse_line("Refs: "), None);
+        assert_eq!(Footer::parse_line(": value"), None);
+    }
+
+    #[test]
+    fn parses_gitmoji_headers() {
+        let header = |message: &str| {
+            let commit: ConventionalCommit = message.parse().unwrap();
+            let gitmoji = commit.gitmoji.map(|g| (g.text, g.position));
+            (gitmoji, commit.commit_type, commit.scope)
+        };
+        let gitmoji = |text: &str, position| Some((text.to_string(), position));
+        assert_eq!(
+            header("✨ feat(ui): x"),
+            (
+                gitmoji("✨", Position::Before),
+                "feat".into(),
+                Some("ui".into())
+            )
+        );
+        assert_eq!(
+            header("✨feat: x"),
+            (gitmoji("✨", Position::Before), "feat".into(), None)
+        );
+        assert_eq!(
+            header(":sparkles:(ui): x"),
+            (
+                gitmoji(":sparkles:", Position::Replace),
+                String::new(),
+                Some("ui".into())
+            )
+        );
+        assert_eq!(
+            header("\u{26a1}: x"),
+            (gitmoji("\u{26a1}", Position::Replace), String::new(), None)
+        );
+        assert_eq!(header("feat: x"), (None, "feat".into(), None));
+        for message in ["✨ feat(ui): x", ":sparkles:(ui)!: x"] {
+            assert_eq!(
+                message.parse::<ConventionalCommit>().unwrap().to_string(),
+                message
+            );
+        }
+    }
+}
@@ -0,0 +1,401 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_json::Value;
+
+use crate::{
+    config::{Config, ConfigError, TypeConfig},
+    git,
+    rules::{Limit, Severity},
+};
+
+/// commitlint config files convit can read, in the order commitlint looks
+/// for them. JavaScript and TypeScript configs can't be evaluated, so they
+/// are not listed.
+pub const CONFIG_FILES: [&str; 7] = [
+    ".commitlintrc",
+    ".commitlintrc.json",
+    ".commitlintrc.yaml",
+    ".commitlintrc.yml",
+    "commitlint.config.json",
+    "commitlint.config.yaml",
+    "commitlint.config.yml",
+];
+
+/// Shared configs whose rules match convit's built-in defaults.
+const BUILT_IN_EXTENDS: [&str; 2] = [
+    "@commitlint/config-conventional",
+    "@commitlint/config-angular",
+];
+
+/// The first commitlint config file at the top of the current working tree.
+pub fn find() -> Option<PathBuf> {
+    let toplevel = git::toplevel().ok()?;
+    CONFIG_FILES
+        .iter()
+        .map(|name| toplevel.join(name))
+        .find(|path| path.is_file())
+}
+
+/// Applies the rules of a commitlint config file to `config`, returning a
+/// warning for each setting convit can't honour.
+pub fn import(path: &Path, config: &mut Config) -> Result<Vec<String>, ConfigError> {
+    let text = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let invalid = |message: String| ConfigError::Invalid {
+        path: path.to_path_buf(),
+        message,
+    };
+
+    // JSON is tried first since YAML parsers can reject JSON that is
+    // indented with tabs.
+    let is_json = path.extension().is_some_and(|ext| ext == "json");
+    let value: Value = match serde_json::from_str(&text) {
+        Ok(value) => value,
+        Err(source) if is_json => {
+            return Err(ConfigError::Parse {
+                path: path.to_path_buf(),
+                source,
+            })
+        }
+        Err(_) => serde_yaml::from_str(&text).map_err(|source| ConfigError::Yaml {
+            path: path.to_path_buf(),
+            source,
+        })?,
+    };
+    let Value::Object(settings) = value else {
+        return Err(invalid("expected a mapping of settings".to_string()));
+    };
+
+    let mut warnings = Vec::new();
+    let extends = match settings.get("extends") {
+        Some(Value::String(name)) => vec![name.clone()],
+        Some(Value::Array(names)) => names
+            .iter()
+            .filter_map(|name| name.as_str().map(str::to_string))
+            .collect(),
+        _ => Vec::new(),
+    };
+    for name in extends {
+        if !BUILT_IN_EXTENDS.contains(&name.as_str()) {
+            warnings.push(format!(
+                "can't load shared config `{name}`, only its rules listed here are used"
+            ));
+        }
+    }
+
+    let Some(rules) = settings.get("rules") else {
+        return Ok(warnings);
+    };
+    let Value::Object(rules) = rules else {
+        return Err(invalid("`rules` must be a mapping".to_string()));
+    };
+    for (name, value) in rules {
+        let rule = Rule::parse(value).ok_or_else(|| {
+            invalid(format!(
+                "rule `{name}` must be [level, \"always\" | \"never\", value]"
+            ))
+        })?;
+        if !apply(name, &rule, config).map_err(invalid)? {
+            warnings.push(format!(
+                "commitlint rule `{name}` is not supported, ignoring it"
+            ));
+        }
+    }
+    Ok(warnings)
+}
+
+/// A commitlint rule setting, `[level, applicable, value]`.
+struct Rule<'a> {
+    severity: Severity,
+    /// `true` for `always`, `false` for `never`.
+    always: bool,
+    value: Option<&'a Value>,
+}
+
+impl<'a> Rule<'a> {
+    fn parse(value: &'a Value) -> Option<Rule<'a>> {
+        let setting = value.as_array()?;
+        let severity = match setting.first()?.as_u64()? {
+            0 => Severity::Off,
+            1 => Severity::Warn,
+            2 => Severity::Error,
+            _ => return None,
+        };
+        let always = match setting.get(1).map(Value::as_str) {
+            None | Some(Some("always")) => true,
+            Some(Some("never")) => false,
+            Some(_) => return None,
+        };
+        Some(Rule {
+            severity,
+            always,
+            value: setting.get(2),
+        })
+    }
+
+    fn strings(&self) -> Result<Vec<String>, String> {
+        let values = self
+            .value
+            .and_then(Value::as_array)
+            .ok_or("expected a list of names")?;
+        values
+            .iter()
+            .map(|value| {
+                value
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| "expected a list of names".to_string())
+            })
+            .collect()
+    }
+
+    fn number(&self) -> Result<usize, String> {
+        self.value
+            .and_then(Value::as_u64)
+            .map(|n| n as usize)
+            .ok_or_else(|| "expected a number".to_string())
+    }
+}
+
+/// Maps one commitlint rule onto the config. Returns false if convit has
+/// no equivalent.
+fn apply(name: &str, rule: &Rule, config: &mut Config) -> Result<bool, String> {
+    let rules = &mut config.rules;
+    let context = |err: String| format!("rule `{name}`: {err}");
+    match (name, rule.always) {
+        ("type-enum", true) => {
+            rules.type_enum = rule.severity;
+            if rule.severity != Severity::Off {
+                // Keep descriptions and required footers of known types.
+                let mut known = std::mem::take(&mut config.types);
+                config.types = rule
+                    .strings()
+                    .map_err(context)?
+                    .into_iter()
+                    .map(|name| match known.iter().position(|t| t.name == name) {
+                        Some(i) => known.swap_remove(i),
+                        None => TypeConfig {
+                            name,
+                            description: String::new(),
+                            required_footers: Vec::new(),
+                            gitmoji: None,
+                        },
+                    })
+                    .collect();
+            }
+        }
+        ("scope-enum", true) => {
+            rules.scope_enum = rule.severity;
+            if rule.severity != Severity::Off {
+                config.scopes = rule.strings().map_err(context)?;
+            }
+        }
+        ("type-case", true) if rule.value.and_then(Value::as_str) == Some("lower-case") => {
+            rules.type_lowercase = rule.severity;
+        }
+        ("header-max-length", true) => {
+            rules.header_max_length = Limit {
+                severity: rule.severity,
+                max: rule.number().map_err(context)?,
+            };
+        }
+        ("body-max-line-length", true) => {
+            rules.body_max_line_length = Limit {
+                severity: rule.severity,
+                max: rule.number().map_err(context)?,
+            };
+        }
+        ("subject-empty", false) => rules.description_empty = rule.severity,
+        ("subject-full-stop", false) if rule.value.and_then(Value::as_str) == Some(".") => {
+            rules.description_full_stop = rule.severity;
+        }
+        ("body-leading-blank", true) => rules.body_leading_blank = rule.severity,
+        // Convit never accepts a commit without a type.
+        ("type-empty", false) => {}
+        _ => return Ok(rule.severity == Severity::Off),
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config
+            .merge_json(
+                r#"{"types": [{"name": "feat", "description": "A feature"}, {"name": "fix"}]}"#,
+            )
+            .unwrap();
+        config
+    }
+
+    fn apply_json(name: &str, value: Value, config: &mut Config) -> Result<bool, String> {
+        apply(name, &Rule::parse(&value).unwrap(), config)
+    }
+
+    /// Imports a config file written to a scratch directory.
+    fn import_file(name: &str, text: &str) -> Result<(Config, Vec<String>), ConfigError> {
+        let dir = std::env::temp_dir().join(format!("convit-commitlint-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        fs::write(&path, text).unwrap();
+        let mut config = config();
+        let result = import(&path, &mut config);
+        let _ = fs::remove_file(&path);
+        result.map(|warnings| (config, warnings))
+    }
+
+    #[test]
+    fn parses_rule_settings() {
+        let parse = |value: Value| {
+            Rule::parse(&value).map(|rule| (rule.severity, rule.always, rule.value.cloned()))
+        };
+        assert_eq!(
+            parse(json!([2, "always", 72])),
+            Some((Severity::Error, true, Some(json!(72))))
+        );
+        assert_eq!(
+            parse(json!([1, "never"])),
+            Some((Severity::Warn, false, None))
+        );
+        assert_eq!(parse(json!([0])), Some((Severity::Off, true, None)));
+        assert_eq!(parse(json!([3, "always"])), None);
+        assert_eq!(parse(json!([2, "sometimes"])), None);
+        assert_eq!(parse(json!([])), None);
+        assert_eq!(parse(json!("error")), None);
+    }
+
+    #[test]
+    fn applies_enums_at_each_level() {
+        let mut config = config();
+        assert_eq!(
+            apply_json(
+                "type-enum",
+                json!([1, "always", ["docs", "feat"]]),
+                &mut config
+            ),
+            Ok(true)
+        );
+        assert_eq!(config.rules.type_enum, Severity::Warn);
+        assert_eq!(config.type_names(), ["docs", "feat"]);
+        assert_eq!(config.types[1].description, "A feature");
+
+        // Turning a rule off keeps the lists.
+        assert_eq!(
+            apply_json("type-enum", json!([0, "always", ["x"]]), &mut config),
+            Ok(true)
+        );
+        assert_eq!(config.rules.type_enum, Severity::Off);
+        assert_eq!(config.type_names(), ["docs", "feat"]);
+
+        assert_eq!(
+            apply_json(
+                "scope-enum",
+                json!([2, "always", ["api", "ui"]]),
+                &mut config
+            ),
+            Ok(true)
+        );
+        assert_eq!(config.rules.scope_enum, Severity::Error);
+        assert_eq!(config.scopes, ["api", "ui"]);
+        assert_eq!(
+            apply_json("scope-enum", json!([2, "always", "api"]), &mut config),
+            Err("rule `scope-enum`: expected a list of names".to_string())
+        );
+    }
+
+    #[test]
+    fn applies_limits_and_never_rules() {
+        let mut config = config();
+        assert_eq!(
+            apply_json("header-max-length", json!([1, "always", 72]), &mut config),
+            Ok(true)
+        );
+        assert_eq!(
+            config.rules.header_max_length,
+            Limit {
+                severity: Severity::Warn,
+                max: 72
+            }
+        );
+        assert_eq!(
+            apply_json("header-max-length", json!([2, "always", "72"]), &mut config),
+            Err("rule `header-max-length`: expected a number".to_string())
+        );
+        assert_eq!(
+            apply_json("subject-empty", json!([1, "never"]), &mut config),
+            Ok(true)
+        );
+        assert_eq!(config.rules.description_empty, Severity::Warn);
+
+        // Only the `never` or `always` form convit can check is supported.
+        assert_eq!(
+            apply_json("type-enum", json!([2, "never", ["wip"]]), &mut config),
+            Ok(false)
+        );
+        assert_eq!(
+            apply_json("subject-empty", json!([2, "always"]), &mut config),
+            Ok(false)
+        );
+        assert_eq!(
+            apply_json("subject-empty", json!([0, "always"]), &mut config),
+            Ok(true)
+        );
+        assert_eq!(config.type_names(), ["feat", "fix"]);
+    }
+
+    #[test]
+    fn imports_json_files() {
+        let (config, warnings) = import_file(
+            ".commitlintrc.json",
+            r#"{
+                "extends": ["@commitlint/config-conventional"],
+                "rules": {
+                    "type-enum": [2, "always", ["feat", "fix", "docs"]],
+                    "header-max-length": [2, "always", 50]
+                }
+            }"#,
+        )
+        .unwrap();
+        assert!(warnings.is_empty(), "{warnings:?}");
+        assert_eq!(config.type_names(), ["feat", "fix", "docs"]);
+        assert_eq!(config.rules.header_max_length.max, 50);
+
+        assert!(matches!(
+            import_file(".commitlintrc.json", "rules:\n  type-enum: [2]\n"),
+            Err(ConfigError::Parse { .. })
+        ));
+    }
+
+    #[test]
+    fn imports_yaml_files_and_warns_about_the_rest() {
+        let (config, warnings) = import_file(
+            ".commitlintrc.yml",
+            "extends: ./shared\nrules:\n  scope-enum: [1, always, [api]]\n  subject-case: [2, never, [upper-case]]\n  signed-off-by: [0, always]\n",
+        )
+        .unwrap();
+        assert_eq!(config.scopes, ["api"]);
+        assert_eq!(config.rules.scope_enum, Severity::Warn);
+        assert_eq!(
+            warnings,
+            [
+                "can't load shared config `./shared`, only its rules listed here are used",
+                "commitlint rule `subject-case` is not supported, ignoring it",
+            ]
+        );
+
+        assert!(matches!(
+            import_file(".commitlintrc.yml", "rules:\n  type-enum: [5, always]\n"),
+            Err(ConfigError::Invalid { message, .. }) if message.contains("type-enum")
+        ));
+    }
+}
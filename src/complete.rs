@@ -0,0 +1,111 @@
+/// How well `query` matches `candidate` as a case-insensitive subsequence,
+/// lower being better, or `None` if it doesn't. Matches at the start of
+/// words and runs of adjacent characters score best.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<usize> {
+    let mut score = 0;
+    // Positions count characters; byte offsets are only for slicing.
+    let mut chars = candidate.char_indices().enumerate();
+    let mut previous: Option<usize> = None;
+    for wanted in query.chars().flat_map(char::to_lowercase) {
+        if wanted.is_whitespace() {
+            continue;
+        }
+        let (position, (offset, _)) = chars
+            .by_ref()
+            .find(|(_, (_, c))| c.to_lowercase().eq(wanted.to_lowercase()))?;
+        let word_start = !candidate[..offset]
+            .chars()
+            .next_back()
+            .is_some_and(char::is_alphanumeric);
+        score += match previous {
+            Some(previous) if previous + 1 == position => 0,
+            _ if word_start => 1,
+            Some(previous) => 2 + (position - previous).min(8),
+            None => 2 + position.min(8),
+        };
+        previous = Some(position);
+    }
+    Some(score)
+}
+
+/// The candidates matching `query`, best first. Candidates that match
+/// equally well keep their order, so frequent ones can be listed first.
+pub fn complete<'a>(query: &str, candidates: &'a [String]) -> Vec<&'a str> {
+    let mut matches: Vec<(usize, &str)> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            fuzzy_score(query, candidate).map(|score| (score, candidate.as_str()))
+        })
+        .collect();
+    matches.sort_by_key(|(score, _)| *score);
+    matches
+        .into_iter()
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn scores_runs_and_word_starts_best() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("ada", "Ada Lovelace"), Some(1));
+        assert_eq!(fuzzy_score("al", "Ada Lovelace"), Some(2));
+        assert_eq!(fuzzy_score("de", "Ada Lovelace"), Some(11));
+        assert_eq!(fuzzy_score("a l", "Ada Lovelace"), Some(2));
+        assert_eq!(fuzzy_score("LOVE", "Ada Lovelace"), Some(1));
+        assert_eq!(fuzzy_score("xyz", "Ada Lovelace"), None);
+        assert_eq!(fuzzy_score("lll", "Ada Lovelace"), None);
+    }
+
+    #[test]
+    fn counts_characters_rather_than_bytes() {
+        // Each run scores as well as it would in ASCII.
+        assert_eq!(fuzzy_score("josé", "José Núñez"), Some(1));
+        assert_eq!(fuzzy_score("núñez", "José Núñez"), Some(1));
+        assert_eq!(fuzzy_score("ukasz", "Łukasz"), Some(3));
+        assert_eq!(fuzzy_score("łuk", "łukasz"), fuzzy_score("luk", "lukasz"));
+        assert_eq!(
+            fuzzy_score("sz", "Łukasz Nowak"),
+            fuzzy_score("sz", "Lukasz Nowak")
+        );
+    }
+
+    #[test]
+    fn ranks_candidates() {
+        let candidates = names(&[
+            "Grace Hopper <grace@example.com>",
+            "Ada Lovelace <ada@example.com>",
+            "Adam Smith <adam@example.com>",
+            "Łukasz Nowak <lukasz@example.com>",
+        ]);
+        assert_eq!(
+            complete("ada", &candidates),
+            [
+                "Ada Lovelace <ada@example.com>",
+                "Adam Smith <adam@example.com>"
+            ]
+        );
+        assert_eq!(
+            complete("nowak", &candidates),
+            ["Łukasz Nowak <lukasz@example.com>"]
+        );
+        assert_eq!(complete("zq", &candidates).len(), 0);
+    }
+
+    #[test]
+    fn keeps_the_order_of_ties() {
+        let candidates = names(&["Bo Two", "Bo One", "Bo Three"]);
+        assert_eq!(
+            complete("bo", &candidates),
+            ["Bo Two", "Bo One", "Bo Three"]
+        );
+        assert_eq!(complete("", &candidates), ["Bo Two", "Bo One", "Bo Three"]);
+    }
+}
@@ -0,0 +1,432 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::{
+    commit::{ConventionalCommit, Footer},
+    commitlint, git,
+    gitmoji::{self, Gitmoji},
+    rules::Rules,
+    template,
+};
+
+/// File name of the repository config, looked up at the top of the working tree.
+pub const REPO_CONFIG_FILE: &str = ".convit.json";
+
+/// A commit type offered in the commit type list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Footer tokens commits of this type must have.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub required_footers: Vec<String>,
+    /// The gitmoji for this type, as an emoji or shortcode from the
+    /// catalog. Built-in types have one already.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitmoji: Option<String>,
+}
+
+/// Writing a gitmoji into every header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GitmojiConfig {
+    #[serde(default)]
+    pub format: gitmoji::Format,
+    #[serde(default)]
+    pub position: gitmoji::Position,
+}
+
+/// What a footer's value has to look like.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FooterValue {
+    /// Any text that isn't blank.
+    #[default]
+    Text,
+    /// `Name <email>`, as in `Signed-off-by`.
+    Person,
+    /// Issue references such as `#123`, `PROJ-123` or `owner/repo#123`,
+    /// links or commit hashes, separated by commas.
+    Reference,
+    /// The one GitHub or GitLab issue a closing keyword such as `Closes`
+    /// or `Resolves` applies to: `#123`, `group/project#123` or a link.
+    Issue,
+    /// Jira issue keys such as `PROJ-123`, separated by commas.
+    Jira,
+    /// Jira smart commit commands, such as `PROJ-123 #time 2h #comment
+    /// Done` or a transition like `PROJ-123 #close`.
+    SmartCommit,
+}
+
+/// A footer offered in the footer list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FooterConfig {
+    pub token: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub value: FooterValue,
+}
+
+impl FooterConfig {
+    /// Whether a parsed footer is one of this kind. `BREAKING CHANGE` also
+    /// matches its `BREAKING-CHANGE` synonym.
+    pub fn matches(&self, footer: &Footer) -> bool {
+        if Footer::new(self.token.as_str(), "").is_breaking_change() {
+            footer.is_breaking_change()
+        } else {
+            footer.token.eq_ignore_ascii_case(&self.token)
+        }
+    }
+}
+
+/// A regular expression, checked when the config is read.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    pub fn new(pattern: &str) -> Result<Self, regex::Error> {
+        Regex::new(pattern).map(Pattern)
+    }
+
+    pub fn regex(&self) -> &Regex {
+        &self.0
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Pattern::new(&pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+/// How to find a ticket reference in the name of the current branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BranchPattern {
+    /// Matched against the branch name. A `ticket` group picks the
+    /// reference out of the match and a `scope` group suggests a scope.
+    pub pattern: Pattern,
+    /// The reference built from the groups, like `#${number}`. The
+    /// `ticket` group, or else the whole match, by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    /// The footer the reference is added as.
+    #[serde(default = "default_ticket_footer")]
+    pub footer: String,
+}
+
+fn default_ticket_footer() -> String {
+    "Refs".to_string()
+}
+
+/// A body skeleton loaded into the editor when a type is picked.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateConfig {
+    #[serde(rename = "type")]
+    pub commit_type: String,
+    /// Only used for this scope. Templates without one apply to any scope.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// May use `{branch}`, `{ticket}` and `{staged_files}`. Section
+    /// headings can't read as footers once filled in, as `Testing:` would.
+    #[serde(deserialize_with = "template_body")]
+    pub body: String,
+}
+
+fn template_body<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let body = String::deserialize(deserializer)?;
+    match template::trailer_heading(&body) {
+        Some(heading) => Err(serde::de::Error::custom(format!(
+            "template line `{heading}` would be read as a footer once filled in; \
+             write headings without a colon, like `Testing`"
+        ))),
+        None => Ok(body),
+    }
+}
+
+/// Settings merged from the built-in defaults, the user config, a
+/// commitlint config and the repository config, in that order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    pub types: Vec<TypeConfig>,
+    pub footers: Vec<FooterConfig>,
+    /// Allowed scopes. Any scope is allowed when empty.
+    pub scopes: Vec<String>,
+    /// Footer tokens every commit must have.
+    pub required_footers: Vec<String>,
+    pub default_type: Option<String>,
+    pub default_scope: Option<String>,
+    pub default_footers: Vec<String>,
+    /// Tried in order until one finds a ticket in the branch name.
+    pub branch_patterns: Vec<BranchPattern>,
+    /// Gitmoji mode, off unless set.
+    pub gitmoji: Option<GitmojiConfig>,
+    /// Body templates, by type and scope.
+    pub templates: Vec<TemplateConfig>,
+    pub rules: Rules,
+    /// Settings from other tools' config files that couldn't be applied.
+    #[serde(skip)]
+    pub warnings: Vec<String>,
+}
+
+/// One config file. Every field that is present replaces the value from the
+/// layers below it, so a repository listing `types` gets exactly those types.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Layer {
+    types: Option<Vec<TypeConfig>>,
+    footers: Option<Vec<FooterConfig>>,
+    scopes: Option<Vec<String>>,
+    required_footers: Option<Vec<String>>,
+    default_type: Option<String>,
+    default_scope: Option<String>,
+    default_footers: Option<Vec<String>>,
+    branch_patterns: Option<Vec<BranchPattern>>,
+    gitmoji: Option<GitmojiConfig>,
+    templates: Option<Vec<TemplateConfig>>,
+    /// Merged rule by rule rather than replaced as a whole.
+    rules: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}: {source}", path.display())]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("{}: {source}", path.display())]
+    Yaml {
+        path: PathBuf,
+        source: serde_yaml::Error,
+    },
+    #[error("{}: {message}", path.display())]
+    Invalid { path: PathBuf, message: String },
+}
+
+impl Config {
+    /// Merges the user config, the repository's commitlint config and the
+    /// repository config, where present, over `defaults`.
+    pub fn load(defaults: Config) -> Result<Config, ConfigError> {
+        let mut config = defaults;
+        if let Some(path) = user_config_path() {
+            config.merge_file(&path)?;
+        }
+        if let Some(path) = commitlint::find() {
+            let warnings = commitlint::import(&path, &mut config)?;
+            config.warnings.extend(
+                warnings
+                    .into_iter()
+                    .map(|warning| format!("{}: {warning}", path.display())),
+            );
+        }
+        if let Some(path) = repo_config_path() {
+            config.merge_file(&path)?;
+        }
+        Ok(config)
+    }
+
+    /// Merges a JSON config file over this one. Missing files are skipped.
+    pub fn merge_file(&mut self, path: &Path) -> Result<(), ConfigError> {
+        let json = match fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(source) => {
+                return Err(ConfigError::Io {
+                    path: path.to_path_buf(),
+                    source,
+                })
+            }
+        };
+        self.merge_json(&json).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Merges a JSON config over this one.
+    pub fn merge_json(&mut self, json: &str) -> Result<(), serde_json::Error> {
+        let layer: Layer = serde_json::from_str(json)?;
+        self.types = layer.types.unwrap_or(std::mem::take(&mut self.types));
+        self.footers = layer.footers.unwrap_or(std::mem::take(&mut self.footers));
+        self.scopes = layer.scopes.unwrap_or(std::mem::take(&mut self.scopes));
+        self.required_footers = layer
+            .required_footers
+            .unwrap_or(std::mem::take(&mut self.required_footers));
+        self.default_type = layer.default_type.or(self.default_type.take());
+        self.default_scope = layer.default_scope.or(self.default_scope.take());
+        self.default_footers = layer
+            .default_footers
+            .unwrap_or(std::mem::take(&mut self.default_footers));
+        self.branch_patterns = layer
+            .branch_patterns
+            .unwrap_or(std::mem::take(&mut self.branch_patterns));
+        self.gitmoji = layer.gitmoji.or(self.gitmoji.take());
+        self.templates = layer
+            .templates
+            .unwrap_or(std::mem::take(&mut self.templates));
+        if let Some(overrides) = layer.rules {
+            let mut rules = serde_json::to_value(&self.rules)?;
+            if let serde_json::Value::Object(rules) = &mut rules {
+                rules.extend(overrides);
+            }
+            self.rules = serde_json::from_value(rules)?;
+        }
+        Ok(())
+    }
+
+    pub fn type_names(&self) -> Vec<&str> {
+        self.types.iter().map(|t| t.name.as_str()).collect()
+    }
+
+    pub fn footer_tokens(&self) -> Vec<&str> {
+        self.footers.iter().map(|f| f.token.as_str()).collect()
+    }
+
+    pub fn footer(&self, token: &str) -> Option<&FooterConfig> {
+        self.footers
+            .iter()
+            .find(|footer| footer.token.eq_ignore_ascii_case(token))
+    }
+
+    /// The gitmoji for a commit type: the configured one, or else the one
+    /// convit uses for the type.
+    pub fn gitmoji(&self, commit_type: &str) -> Option<&'static Gitmoji> {
+        self.types
+            .iter()
+            .find(|t| t.name == commit_type)
+            .and_then(|t| t.gitmoji.as_deref())
+            .and_then(gitmoji::find)
+            .or_else(|| gitmoji::for_type(commit_type))
+    }
+
+    /// The type a gitmoji stands for, for headers where it replaces the
+    /// type.
+    pub fn gitmoji_type(&self, text: &str) -> Option<&str> {
+        let wanted = gitmoji::find(text)?;
+        self.types
+            .iter()
+            .find(|t| self.gitmoji(&t.name) == Some(wanted))
+            .map(|t| t.name.as_str())
+    }
+
+    /// The body template for a type, preferring one made for the scope.
+    pub fn template(&self, commit_type: &str, scope: Option<&str>) -> Option<&TemplateConfig> {
+        let for_type = || {
+            self.templates
+                .iter()
+                .filter(|t| t.commit_type == commit_type)
+        };
+        for_type()
+            .find(|t| scope.is_some() && t.scope.as_deref() == scope)
+            .or_else(|| for_type().find(|t| t.scope.is_none()))
+    }
+
+    /// Fills in the type of a commit whose gitmoji replaces it in the
+    /// header.
+    pub fn resolve_gitmoji(&self, commit: &mut ConventionalCommit) {
+        let Some(header) = commit.gitmoji.as_ref() else {
+            return;
+        };
+        if commit.commit_type.is_empty() {
+            if let Some(commit_type) = self.gitmoji_type(&header.text) {
+                commit.commit_type = commit_type.to_string();
+            }
+        }
+    }
+}
+
+/// `$XDG_CONFIG_HOME/convit/config.json`, falling back to `~/.config`.
+pub fn user_config_path() -> Option<PathBuf> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config_home.join("convit").join("config.json"))
+}
+
+/// `.convit.json` at the top of the current working tree, if inside one.
+pub fn repo_config_path() -> Option<PathBuf> {
+    git::toplevel().ok().map(|dir| dir.join(REPO_CONFIG_FILE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_gitmojis_to_types() {
+        let mut config = Config::default();
+        config
+            .merge_json(
+                r#"{"types": [
+                    {"name": "feat"},
+                    {"name": "hotfix", "gitmoji": ":ambulance:"},
+                    {"name": "fix"}
+                ]}"#,
+            )
+            .unwrap();
+        assert_eq!(config.gitmoji("hotfix").map(|g| g.emoji), Some("🚑️"));
+        assert_eq!(config.gitmoji("fix").map(|g| g.code), Some(":bug:"));
+        assert_eq!(config.gitmoji_type("🚑"), Some("hotfix"));
+        assert_eq!(config.gitmoji_type(":bug:"), Some("fix"));
+        assert_eq!(config.gitmoji_type("✨"), Some("feat"));
+        // Only the configured types count.
+        assert_eq!(config.gitmoji_type(":memo:"), None);
+
+        let mut commit: ConventionalCommit = ":ambulance:(db): x".parse().unwrap();
+        config.resolve_gitmoji(&mut commit);
+        assert_eq!(commit.commit_type, "hotfix");
+    }
+
+    #[test]
+    fn prefers_templates_for_the_scope() {
+        let mut config = Config::default();
+        config
+            .merge_json(
+                r#"{"templates": [
+                    {"type": "fix", "scope": "api", "body": "api"},
+                    {"type": "fix", "body": "any"},
+                    {"type": "feat", "scope": "ui", "body": "ui"}
+                ]}"#,
+            )
+            .unwrap();
+        let body = |commit_type, scope| config.template(commit_type, scope).map(|t| &*t.body);
+        assert_eq!(body("fix", Some("api")), Some("api"));
+        assert_eq!(body("fix", Some("db")), Some("any"));
+        assert_eq!(body("fix", None), Some("any"));
+        assert_eq!(body("feat", Some("ui")), Some("ui"));
+        assert_eq!(body("feat", None), None);
+        assert_eq!(body("docs", None), None);
+
+        let error = config
+            .merge_json(r#"{"templates": [{"type": "fix", "body": "Cause\n\nTesting:"}]}"#)
+            .unwrap_err();
+        assert!(error.to_string().contains("`Testing:`"), "{error}");
+    }
+}
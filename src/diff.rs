@@ -0,0 +1,496 @@
+use std::{fmt, ops::Range};
+
+/// One `@@ -a,b +c,d @@` section of a unified diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub old_start: usize,
+    pub old_lines: usize,
+    pub new_start: usize,
+    pub new_lines: usize,
+    /// The lines of the hunk, each starting with ` `, `-` or `+`.
+    pub lines: Vec<String>,
+}
+
+impl Hunk {
+    /// The 1-based line numbers of removed lines in the old file.
+    pub fn removed(&self) -> Vec<usize> {
+        self.numbered('-', self.old_start)
+    }
+
+    /// The 1-based line numbers of added lines in the new file.
+    pub fn added(&self) -> Vec<usize> {
+        self.numbered('+', self.new_start)
+    }
+
+    /// Whether the line at `index` adds or removes something.
+    pub fn is_change(&self, index: usize) -> bool {
+        self.lines
+            .get(index)
+            .is_some_and(|line| line.starts_with(['-', '+']))
+    }
+
+    /// The hunk with only the changes at the given `lines` indices, or
+    /// `None` if none of them is a change.
+    ///
+    /// The patch has to apply to the side that is kept: unselected removals
+    /// stay as context and unselected additions are dropped. With `reverse`,
+    /// for patches applied with `git apply --reverse`, it's the other way
+    /// round.
+    pub fn select(&self, lines: &[usize], reverse: bool) -> Option<Hunk> {
+        if !lines.iter().any(|index| self.is_change(*index)) {
+            return None;
+        }
+        let (keep, drop) = if reverse { ('+', '-') } else { ('-', '+') };
+        let mut selected = Vec::new();
+        let mut dropped = false;
+        for (index, line) in self.lines.iter().enumerate() {
+            let marker = line.chars().next().unwrap_or(' ');
+            if marker == '\\' {
+                // "No newline at end of file" belongs to the line before it.
+                if !dropped {
+                    selected.push(line.clone());
+                }
+                continue;
+            }
+            dropped = false;
+            if lines.contains(&index) || marker == ' ' {
+                selected.push(line.clone());
+            } else if marker == keep {
+                selected.push(format!(" {}", &line[1..]));
+            } else if marker == drop {
+                dropped = true;
+            }
+        }
+        let count = |markers: [char; 2]| {
+            selected
+                .iter()
+                .filter(|line| line.starts_with(markers))
+                .count()
+        };
+        Some(Hunk {
+            old_start: self.old_start,
+            old_lines: count([' ', '-']),
+            new_start: self.new_start,
+            new_lines: count([' ', '+']),
+            lines: selected,
+        })
+    }
+
+    /// The old and new line numbers of each line; removed lines have no
+    /// new number and added lines no old one.
+    pub fn line_numbers(&self) -> Vec<(Option<usize>, Option<usize>)> {
+        let (mut old, mut new) = (self.old_start, self.new_start);
+        self.lines
+            .iter()
+            .map(|line| match line.chars().next() {
+                Some('-') => {
+                    old += 1;
+                    (Some(old - 1), None)
+                }
+                Some('+') => {
+                    new += 1;
+                    (None, Some(new - 1))
+                }
+                Some('\\') => (None, None),
+                _ => {
+                    old += 1;
+                    new += 1;
+                    (Some(old - 1), Some(new - 1))
+                }
+            })
+            .collect()
+    }
+
+    /// The lines side by side, as indices into `lines`. Context lines are
+    /// on both sides; the removals and additions of a change are paired up
+    /// in order, leaving gaps where one side has more lines.
+    pub fn pairs(&self) -> Vec<(Option<usize>, Option<usize>)> {
+        let mut pairs = Vec::new();
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        let mut last = ' ';
+        for (index, line) in self.lines.iter().enumerate() {
+            let mut marker = line.chars().next().unwrap_or(' ');
+            if marker == '\\' {
+                // Belongs to the line before it.
+                marker = last;
+            }
+            match marker {
+                '-' => removed.push(index),
+                '+' => added.push(index),
+                _ => {
+                    flush_change(&mut pairs, &mut removed, &mut added);
+                    pairs.push((Some(index), Some(index)));
+                }
+            }
+            last = marker;
+        }
+        flush_change(&mut pairs, &mut removed, &mut added);
+        pairs
+    }
+
+    fn numbered(&self, wanted: char, start: usize) -> Vec<usize> {
+        let mut number = start;
+        let mut numbers = Vec::new();
+        for line in &self.lines {
+            match line.chars().next() {
+                Some(c) if c == wanted => {
+                    numbers.push(number);
+                    number += 1;
+                }
+                Some(' ') => number += 1,
+                _ => {}
+            }
+        }
+        numbers
+    }
+}
+
+impl fmt::Display for Hunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "@@ -{},{} +{},{} @@",
+            self.old_start, self.old_lines, self.new_start, self.new_lines
+        )?;
+        for line in &self.lines {
+            writeln!(f, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+fn flush_change(
+    pairs: &mut Vec<(Option<usize>, Option<usize>)>,
+    removed: &mut Vec<usize>,
+    added: &mut Vec<usize>,
+) {
+    for i in 0..removed.len().max(added.len()) {
+        pairs.push((removed.get(i).copied(), added.get(i).copied()));
+    }
+    removed.clear();
+    added.clear();
+}
+
+/// The unified diff of a single file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileDiff {
+    /// The `diff --git`, `index`, `---` and `+++` lines.
+    pub header: Vec<String>,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FileDiff {
+    /// Parses the output of `git diff` for one file.
+    pub fn parse(diff: &str) -> Self {
+        let mut file = FileDiff::default();
+        for line in diff.lines() {
+            if let Some(header) = line.strip_prefix("@@ ") {
+                if let Some(hunk) = parse_header(header) {
+                    file.hunks.push(hunk);
+                }
+            } else if let Some(hunk) = file.hunks.last_mut() {
+                if line.starts_with([' ', '-', '+', '\\']) {
+                    hunk.lines.push(line.to_string());
+                }
+            } else {
+                file.header.push(line.to_string());
+            }
+        }
+        file
+    }
+
+    /// The file's path, after a rename.
+    pub fn path(&self) -> &str {
+        let line = |prefix: &str| {
+            self.header
+                .iter()
+                .find_map(|line| line.strip_prefix(prefix))
+                .filter(|path| *path != "/dev/null")
+        };
+        line("+++ b/")
+            .or_else(|| line("--- a/"))
+            .or_else(|| line("rename to "))
+            .or_else(|| {
+                // Binary files and mode changes only have the first line.
+                line("diff --git a/").and_then(|paths| paths.split_once(" b/").map(|(_, b)| b))
+            })
+            .unwrap_or_default()
+    }
+
+    /// A patch of this file with just `hunk`, for `git apply`.
+    pub fn patch(&self, hunk: &Hunk) -> String {
+        let mut patch = self.header.join("\n");
+        patch.push('\n');
+        patch.push_str(&hunk.to_string());
+        patch
+    }
+}
+
+/// Splits the output of `git diff` into its files.
+pub fn parse_files(diff: &str) -> Vec<FileDiff> {
+    let mut files = Vec::new();
+    let mut current = String::new();
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") && !current.is_empty() {
+            files.push(FileDiff::parse(&current));
+            current.clear();
+        }
+        current.push_str(line);
+        current.push('\n');
+    }
+    if !current.is_empty() {
+        files.push(FileDiff::parse(&current));
+    }
+    files
+}
+
+/// Longer lines are compared whole, to keep the comparison quick.
+const MAX_WORDS: usize = 200;
+
+/// The byte ranges of the words that differ between a removed line and
+/// the added line replacing it. Nothing is marked when the lines have no
+/// words in common, since the whole line changed.
+pub fn word_diff(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    let (old_words, new_words) = (words(old), words(new));
+    if old_words.len() > MAX_WORDS || new_words.len() > MAX_WORDS {
+        return (Vec::new(), Vec::new());
+    }
+    let old_text: Vec<&str> = old_words.iter().map(|range| &old[range.clone()]).collect();
+    let new_text: Vec<&str> = new_words.iter().map(|range| &new[range.clone()]).collect();
+
+    // Longest common subsequence of words, by dynamic programming.
+    let (n, m) = (old_words.len(), new_words.len());
+    let mut lengths = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[i][j] = if old_text[i] == new_text[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let (mut old_changed, mut new_changed) = (Vec::new(), Vec::new());
+    let mut common = false;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_text[i] == new_text[j] {
+            common |= !old_text[i].trim().is_empty();
+            i += 1;
+            j += 1;
+        } else if j < m && (i == n || lengths[i][j + 1] >= lengths[i + 1][j]) {
+            push_range(&mut new_changed, new_words[j].clone());
+            j += 1;
+        } else {
+            push_range(&mut old_changed, old_words[i].clone());
+            i += 1;
+        }
+    }
+    if common {
+        (old_changed, new_changed)
+    } else {
+        (Vec::new(), Vec::new())
+    }
+}
+
+/// Splits a line into runs of word characters, runs of whitespace and
+/// single other characters.
+fn words(text: &str) -> Vec<Range<usize>> {
+    let class = |c: char| {
+        if c.is_alphanumeric() || c == '_' {
+            0
+        } else if c.is_whitespace() {
+            1
+        } else {
+            2
+        }
+    };
+    let mut words: Vec<Range<usize>> = Vec::new();
+    let mut last = None;
+    for (i, c) in text.char_indices() {
+        let current = class(c);
+        match words.last_mut() {
+            Some(word) if last == Some(current) && current != 2 => word.end = i + c.len_utf8(),
+            _ => words.push(i..i + c.len_utf8()),
+        }
+        last = Some(current);
+    }
+    words
+}
+
+/// Adds a range, merging it into the previous one when they touch.
+fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+    match ranges.last_mut() {
+        Some(last) if last.end == range.start => last.end = range.end,
+        _ => ranges.push(range),
+    }
+}
+
+/// The hunks of a unified diff of a single file.
+pub fn parse_hunks(diff: &str) -> Vec<Hunk> {
+    FileDiff::parse(diff).hunks
+}
+
+/// Parses `-a,b +c,d @@ ...`, where a missing count means one line.
+fn parse_header(header: &str) -> Option<Hunk> {
+    let mut ranges = header.split_whitespace();
+    let (old_start, old_lines) = parse_range(ranges.next()?.strip_prefix('-')?)?;
+    let (new_start, new_lines) = parse_range(ranges.next()?.strip_prefix('+')?)?;
+    Some(Hunk {
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        lines: Vec::new(),
+    })
+}
+
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    match range.split_once(',') {
+        Some((start, lines)) => Some((start.parse().ok()?, lines.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,4 @@ fn main() {
+ one
+-two
+-three
++TWO
++THREE
+ four
+";
+
+    fn hunk() -> Hunk {
+        FileDiff::parse(DIFF).hunks.remove(0)
+    }
+
+    #[test]
+    fn parses_hunks_and_their_line_numbers() {
+        let file = FileDiff::parse(DIFF);
+        assert_eq!(file.header.len(), 4);
+        let hunk = &file.hunks[0];
+        assert_eq!((hunk.old_start, hunk.old_lines), (1, 4));
+        assert_eq!(hunk.removed(), [2, 3]);
+        assert_eq!(hunk.added(), [2, 3]);
+        assert_eq!(parse_hunks("@@ -5 +5,0 @@\n-gone\n")[0].new_lines, 0);
+    }
+
+    #[test]
+    fn selects_lines_to_stage() {
+        // Staging the first removal and addition only: the other removal
+        // stays as context and the other addition is dropped.
+        let selected = hunk().select(&[1, 3], false).unwrap();
+        assert_eq!(selected.lines, [" one", "-two", " three", "+TWO", " four"]);
+        assert_eq!((selected.old_lines, selected.new_lines), (4, 4));
+        assert_eq!(hunk().select(&[0, 5], false), None);
+    }
+
+    #[test]
+    fn selects_lines_to_unstage() {
+        // Reversed, the patch applies to the new side: unselected additions
+        // stay as context and unselected removals are dropped.
+        let selected = hunk().select(&[1, 3], true).unwrap();
+        assert_eq!(selected.lines, [" one", "-two", "+TWO", " THREE", " four"]);
+        assert_eq!((selected.old_lines, selected.new_lines), (4, 4));
+    }
+
+    #[test]
+    fn keeps_the_no_newline_marker_with_its_line() {
+        let hunk = parse_hunks(
+            "@@ -1 +1 @@\n-old\n\\ No newline at end of file\n+new\n\\ No newline at end of file\n",
+        )
+        .remove(0);
+        let selected = hunk.select(&[0], false).unwrap();
+        assert_eq!(selected.lines, ["-old", "\\ No newline at end of file"]);
+        assert_eq!((selected.old_lines, selected.new_lines), (1, 0));
+    }
+
+    #[test]
+    fn writes_patches_for_git_apply() {
+        let file = FileDiff::parse(DIFF);
+        let patch = file.patch(&file.hunks[0].select(&[1], false).unwrap());
+        assert_eq!(
+            patch,
+            "diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -1,4 +1,3 @@
+ one
+-two
+ three
+ four
+"
+        );
+    }
+
+    #[test]
+    fn numbers_and_pairs_lines() {
+        let hunk = parse_hunks("@@ -10,3 +10,4 @@\n a\n-b\n+B\n+C\n d\n").remove(0);
+        assert_eq!(
+            hunk.line_numbers(),
+            [
+                (Some(10), Some(10)),
+                (Some(11), None),
+                (None, Some(11)),
+                (None, Some(12)),
+                (Some(12), Some(13)),
+            ]
+        );
+        assert_eq!(
+            hunk.pairs(),
+            [
+                (Some(0), Some(0)),
+                (Some(1), Some(2)),
+                (None, Some(3)),
+                (Some(4), Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_changed_words() {
+        // The marked words of both lines.
+        let changed = |old: &str, new: &str| {
+            let (old_ranges, new_ranges) = word_diff(old, new);
+            let words = |line: &str, ranges: Vec<Range<usize>>| {
+                ranges
+                    .into_iter()
+                    .map(|range| line[range].to_string())
+                    .collect::<Vec<_>>()
+            };
+            (words(old, old_ranges), words(new, new_ranges))
+        };
+        assert_eq!(
+            changed("let x = old_value;", "let x = new_value;"),
+            (vec!["old_value".to_string()], vec!["new_value".to_string()])
+        );
+        assert_eq!(changed("a b c", "a c"), (vec!["b ".to_string()], vec![]));
+        // Nothing in common: the whole line changed.
+        assert_eq!(changed("foo", "bar"), (vec![], vec![]));
+    }
+
+    #[test]
+    fn splits_diffs_by_file() {
+        let second = DIFF.replace("src/lib.rs", "src/main.rs");
+        let files = parse_files(&format!("{DIFF}{second}"));
+        let paths: Vec<&str> = files.iter().map(FileDiff::path).collect();
+        assert_eq!(paths, ["src/lib.rs", "src/main.rs"]);
+        let deleted = FileDiff::parse(
+            "diff --git a/old.rs b/old.rs\ndeleted file mode 100644\n--- a/old.rs\n+++ /dev/null\n",
+        );
+        assert_eq!(deleted.path(), "old.rs");
+        let binary = FileDiff::parse("diff --git a/logo.png b/logo.png\nBinary files differ\n");
+        assert_eq!(binary.path(), "logo.png");
+    }
+}
@@ -0,0 +1,484 @@
+use std::{
+    ffi::OsStr,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use thiserror::Error;
+
+/// The comment character git uses in commit message files, from
+/// `core.commentChar`. Falls back to `#` when unset or set to `auto`.
+pub fn comment_char() -> char {
+    Command::new("git")
+        .args(["config", "core.commentChar"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| {
+            let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            if value == "auto" {
+                None
+            } else {
+                value.chars().next()
+            }
+        })
+        .unwrap_or('#')
+}
+
+/// The lines of a commit message file that git keeps, numbered from 1.
+/// Comment lines and everything below the scissors line that
+/// `git commit --verbose` adds are left out.
+pub fn message_lines(message: &str, comment_char: char) -> Vec<(usize, &str)> {
+    let scissors = format!("{comment_char} ------------------------ >8 ------------------------");
+
+    message
+        .lines()
+        .enumerate()
+        .take_while(|(_, line)| *line != scissors)
+        .filter(|(_, line)| !line.starts_with(comment_char))
+        .map(|(i, line)| (i + 1, line))
+        .collect()
+}
+
+/// Removes git's comment lines and the `--verbose` diff from a commit message file.
+pub fn strip_comments(message: &str, comment_char: char) -> String {
+    message_lines(message, comment_char)
+        .into_iter()
+        .map(|(_, line)| line)
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Errors from running git.
+#[derive(Debug, Error)]
+pub enum GitError {
+    #[error("failed to run git: {0}")]
+    Io(#[from] io::Error),
+    /// git ran but exited unsuccessfully, e.g. a hook rejected the commit.
+    #[error("{0}")]
+    Failed(String),
+}
+
+/// Flags for `git commit`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CommitOptions {
+    pub amend: bool,
+    pub signoff: bool,
+    pub no_verify: bool,
+    pub allow_empty: bool,
+}
+
+impl CommitOptions {
+    fn args(&self) -> Vec<&'static str> {
+        [
+            (self.amend, "--amend"),
+            (self.signoff, "--signoff"),
+            (self.no_verify, "--no-verify"),
+            (self.allow_empty, "--allow-empty"),
+        ]
+        .into_iter()
+        .filter_map(|(enabled, flag)| enabled.then_some(flag))
+        .collect()
+    }
+}
+
+/// Runs `git commit` in the current repository with the given message,
+/// returning git's summary of the new commit.
+pub fn commit(message: &str, options: CommitOptions) -> Result<String, GitError> {
+    let mut args = vec!["commit", "--file", "-"];
+    args.extend(options.args());
+    run_with_input(&args, message)
+}
+
+/// Runs git with `input` on stdin, returning its trimmed stdout.
+fn run_with_input(args: &[&str], input: &str) -> Result<String, GitError> {
+    let mut child = Command::new("git")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    if output.status.success() {
+        Ok(stdout)
+    } else {
+        // "nothing to commit" goes to stdout, hook output usually to stderr.
+        let details = [stderr, stdout]
+            .into_iter()
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        Err(GitError::Failed(details))
+    }
+}
+
+/// Runs git with the given arguments and returns its trimmed stdout.
+fn output(args: &[impl AsRef<OsStr>]) -> Result<String, GitError> {
+    raw_output(args).map(|output| output.trim().to_string())
+}
+
+/// Like [`output`], without trimming.
+fn raw_output(args: &[impl AsRef<OsStr>]) -> Result<String, GitError> {
+    let output = Command::new("git").args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        Err(GitError::Failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// The directory git runs hooks from. `core.hooksPath` wins when set;
+/// otherwise this is the common `hooks` directory, which linked worktrees
+/// share with the main one.
+pub fn hooks_dir() -> Result<PathBuf, GitError> {
+    if let Ok(hooks_path) = output(&["config", "core.hooksPath"]) {
+        if !hooks_path.is_empty() {
+            let hooks_path = PathBuf::from(hooks_path);
+            if hooks_path.is_absolute() {
+                return Ok(hooks_path);
+            }
+            // Relative paths are resolved from the top of the working tree.
+            return Ok(toplevel()?.join(hooks_path));
+        }
+    }
+    let common_dir = output(&["rev-parse", "--path-format=absolute", "--git-common-dir"])?;
+    Ok(Path::new(&common_dir).join("hooks"))
+}
+
+/// The top of the current working tree.
+pub fn toplevel() -> Result<PathBuf, GitError> {
+    output(&["rev-parse", "--show-toplevel"]).map(PathBuf::from)
+}
+
+/// The name of the checked-out branch, or `None` on a detached `HEAD`.
+pub fn current_branch() -> Option<String> {
+    output(&["symbolic-ref", "--quiet", "--short", "HEAD"]).ok()
+}
+
+/// A commit listed by [`log`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    pub hash: String,
+    pub short_hash: String,
+    pub message: String,
+    /// Files the commit changed, relative to the top of the working tree.
+    pub paths: Vec<String>,
+}
+
+/// The non-merge commits in a revision range such as `v1.0.0..HEAD`, newest first.
+pub fn log(range: &str) -> Result<Vec<LogEntry>, GitError> {
+    // Each commit starts with a record separator and its fields end with
+    // unit separators, neither of which turn up in commit messages. The
+    // changed files follow the last field.
+    let log = output(&[
+        "-c",
+        "core.quotePath=false",
+        "log",
+        "--no-merges",
+        "--name-only",
+        "--format=%x1e%H%x1f%h%x1f%B%x1f",
+        range,
+        "--",
+    ])?;
+    Ok(log
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.splitn(4, '\x1f');
+            Some(LogEntry {
+                hash: fields.next()?.to_string(),
+                short_hash: fields.next()?.to_string(),
+                message: fields.next()?.trim().to_string(),
+                paths: fields
+                    .next()?
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect())
+}
+
+/// The most recent tag reachable from `rev`, if there is one.
+pub fn latest_tag(rev: &str) -> Option<String> {
+    output(&["describe", "--tags", "--abbrev=0", rev]).ok()
+}
+
+/// Whether `rev` names a tag.
+pub fn is_tag(rev: &str) -> bool {
+    output(&[
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("refs/tags/{rev}"),
+    ])
+    .is_ok()
+}
+
+/// The committer date of `rev` as `YYYY-MM-DD`.
+pub fn commit_date(rev: &str) -> Result<String, GitError> {
+    output(&["log", "-1", "--format=%cs", rev])
+}
+
+/// Tag names reachable from `rev`.
+pub fn tags(rev: &str) -> Result<Vec<String>, GitError> {
+    let tags = output(&["tag", "--list", "--merged", rev])?;
+    Ok(tags.lines().map(str::to_string).collect())
+}
+
+/// Creates an annotated tag on `HEAD`. The message is kept verbatim, so
+/// Markdown headings aren't mistaken for comments.
+pub fn create_tag(name: &str, message: &str) -> Result<(), GitError> {
+    run_with_input(
+        &[
+            "tag",
+            "--annotate",
+            "--cleanup=verbatim",
+            "--file",
+            "-",
+            name,
+        ],
+        message,
+    )
+    .map(|_| ())
+}
+
+/// Stages the given paths.
+pub fn add(paths: &[PathBuf]) -> Result<(), GitError> {
+    let mut args = vec![OsStr::new("add"), OsStr::new("--")];
+    args.extend(paths.iter().map(|path| path.as_os_str()));
+    output(&args).map(|_| ())
+}
+
+/// A file in the index that differs from `HEAD`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StagedFile {
+    /// git's status letter: `A`dded, `M`odified, `D`eleted, `R`enamed, ...
+    pub status: char,
+    pub path: String,
+    /// The path before a rename or copy.
+    pub old_path: Option<String>,
+}
+
+/// The staged changes, from `git diff --cached --name-status`.
+pub fn staged_files() -> Result<Vec<StagedFile>, GitError> {
+    let output = output(&["diff", "--cached", "--name-status", "-z"])?;
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut files = Vec::new();
+    while let Some(status) = fields.next() {
+        let status = status.chars().next().unwrap_or('M');
+        let Some(first) = fields.next() else {
+            break;
+        };
+        // Renames and copies list the old path first.
+        let file = if matches!(status, 'R' | 'C') {
+            StagedFile {
+                status,
+                path: fields.next().unwrap_or(first).to_string(),
+                old_path: Some(first.to_string()),
+            }
+        } else {
+            StagedFile {
+                status,
+                path: first.to_string(),
+                old_path: None,
+            }
+        };
+        files.push(file);
+    }
+    Ok(files)
+}
+
+/// The staged diff of one file, without context lines.
+pub fn staged_diff(path: &str) -> Result<String, GitError> {
+    output(&["diff", "--cached", "--unified=0", "--", &top_pathspec(path)])
+}
+
+/// A pathspec matching exactly `path`, relative to the top of the working
+/// tree wherever git is run from.
+fn top_pathspec(path: &str) -> String {
+    format!(":(top,literal){path}")
+}
+
+/// The contents of a file as staged (`None`) or at a revision.
+pub fn show_file(rev: Option<&str>, path: &str) -> Result<String, GitError> {
+    let object = format!("{}:{path}", rev.unwrap_or_default());
+    raw_output(&["show", &object])
+}
+
+/// A changed or untracked file in the working tree, from `git status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusEntry {
+    /// The status of the staged change, or ` ` if there is none.
+    pub index: char,
+    /// The status of the unstaged change, or ` ` if there is none.
+    pub worktree: char,
+    pub path: String,
+    /// The path before a staged rename or copy.
+    pub old_path: Option<String>,
+}
+
+impl StatusEntry {
+    /// Whether the file isn't tracked yet.
+    pub fn is_untracked(&self) -> bool {
+        self.index == '?'
+    }
+
+    /// Whether every change to the file is staged.
+    pub fn is_staged(&self) -> bool {
+        !matches!(self.index, ' ' | '?') && self.worktree == ' '
+    }
+}
+
+/// The changed and untracked files, relative to the top of the working tree.
+pub fn status() -> Result<Vec<StatusEntry>, GitError> {
+    let output = raw_output(&["status", "--porcelain=v1", "-z", "--untracked-files=all"])?;
+    Ok(parse_status(&output))
+}
+
+/// Parses `git status --porcelain=v1 -z`.
+fn parse_status(output: &str) -> Vec<StatusEntry> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut entries = Vec::new();
+    while let Some(field) = fields.next() {
+        let mut chars = field.chars();
+        let (Some(index), Some(worktree)) = (chars.next(), chars.next()) else {
+            continue;
+        };
+        // Renames and copies are followed by the old path.
+        let old_path = if matches!(index, 'R' | 'C') {
+            fields.next().map(str::to_string)
+        } else {
+            None
+        };
+        entries.push(StatusEntry {
+            index,
+            worktree,
+            path: field.get(3..).unwrap_or_default().to_string(),
+            old_path,
+        });
+    }
+    entries
+}
+
+/// Stages all changes to a file, including its removal.
+pub fn stage(entry: &StatusEntry) -> Result<(), GitError> {
+    output(&["add", "--all", "--", &top_pathspec(&entry.path)]).map(|_| ())
+}
+
+/// Moves the staged changes to a file back to the working tree. Both sides
+/// of a rename are reset.
+pub fn unstage(entry: &StatusEntry) -> Result<(), GitError> {
+    let mut pathspecs = vec![top_pathspec(&entry.path)];
+    pathspecs.extend(entry.old_path.as_deref().map(top_pathspec));
+    let mut args = vec!["reset", "--quiet", "--"];
+    args.extend(pathspecs.iter().map(String::as_str));
+    output(&args).map(|_| ())
+}
+
+/// The diff of one file between the index and the working tree, or
+/// between `HEAD` and the index when `staged`.
+pub fn diff(path: &str, staged: bool) -> Result<String, GitError> {
+    let pathspec = top_pathspec(path);
+    let mut args = vec!["diff", "--no-color", "--no-ext-diff"];
+    if staged {
+        args.push("--cached");
+    }
+    args.extend(["--", &pathspec]);
+    raw_output(&args)
+}
+
+/// Applies a patch to the index only, as `git add -p` does. With
+/// `reverse`, the patch is taken back out of the index.
+pub fn apply_to_index(patch: &str, reverse: bool) -> Result<(), GitError> {
+    let mut args = vec!["apply", "--cached", "--whitespace=nowarn"];
+    if reverse {
+        args.push("--reverse");
+    }
+    args.push("-");
+    run_with_input(&args, patch).map(|_| ())
+}
+
+/// Everything staged, as `git diff --cached` shows it.
+pub fn staged_patch() -> Result<String, GitError> {
+    raw_output(&[
+        "-c",
+        "core.quotePath=false",
+        "diff",
+        "--cached",
+        "--no-color",
+        "--no-ext-diff",
+    ])
+}
+
+/// The authors of the current branch as `Name <email>`, most commits
+/// first, with `.mailmap` applied. Empty before the first commit.
+pub fn authors() -> Result<Vec<String>, GitError> {
+    if output(&["rev-parse", "--verify", "--quiet", "HEAD"]).is_err() {
+        return Ok(Vec::new());
+    }
+    // Without a revision, shortlog would read a log from stdin.
+    let output = output(&["shortlog", "--summary", "--numbered", "--email", "HEAD"])?;
+    Ok(output
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(_, author)| author.to_string())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(index: char, worktree: char, path: &str, old_path: Option<&str>) -> StatusEntry {
+        StatusEntry {
+            index,
+            worktree,
+            path: path.to_string(),
+            old_path: old_path.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn parses_status_entries() {
+        let output = "?? new file.rs\0MM src/lib.rs\0 D gone.rs\0A  staged.rs\0";
+        assert_eq!(
+            parse_status(output),
+            [
+                entry('?', '?', "new file.rs", None),
+                entry('M', 'M', "src/lib.rs", None),
+                entry(' ', 'D', "gone.rs", None),
+                entry('A', ' ', "staged.rs", None),
+            ]
+        );
+        let entries = parse_status(output);
+        assert!(entries[0].is_untracked());
+        assert!(!entries[1].is_untracked());
+    }
+
+    #[test]
+    fn parses_renames_and_unusual_paths() {
+        let output = "R  new name.rs\0old name.rs\0RM b.rs\0a.rs\0 M line\nbreak.rs\0";
+        assert_eq!(
+            parse_status(output),
+            [
+                entry('R', ' ', "new name.rs", Some("old name.rs")),
+                entry('R', 'M', "b.rs", Some("a.rs")),
+                entry(' ', 'M', "line\nbreak.rs", None),
+            ]
+        );
+        assert_eq!(parse_status(""), []);
+    }
+}
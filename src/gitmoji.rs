@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+
+/// An emoji from the gitmoji catalog, with its shortcode and meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gitmoji {
+    pub emoji: &'static str,
+    pub code: &'static str,
+    pub description: &'static str,
+}
+
+/// How gitmojis are written into the header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// The emoji itself, like `✨`.
+    #[default]
+    Emoji,
+    /// The shortcode, like `:sparkles:`.
+    Shortcode,
+}
+
+/// Where the gitmoji goes in the header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Position {
+    /// `✨ feat(scope): description`.
+    #[default]
+    Before,
+    /// `✨(scope): description`, in place of the type.
+    Replace,
+}
+
+impl Gitmoji {
+    pub fn text(&self, format: Format) -> &'static str {
+        match format {
+            Format::Emoji => self.emoji,
+            Format::Shortcode => self.code,
+        }
+    }
+}
+
+const fn gitmoji(emoji: &'static str, code: &'static str, description: &'static str) -> Gitmoji {
+    Gitmoji {
+        emoji,
+        code,
+        description,
+    }
+}
+
+/// The gitmoji catalog, as published at gitmoji.dev.
+pub const GITMOJIS: [Gitmoji; 75] = [
+    gitmoji("🎨", ":art:", "Improve structure / format of the code."),
+    gitmoji("⚡️", ":zap:", "Improve performance."),
+    gitmoji("🔥", ":fire:", "Remove code or files."),
+    gitmoji("🐛", ":bug:", "Fix a bug."),
+    gitmoji("🚑️", ":ambulance:", "Critical hotfix."),
+    gitmoji("✨", ":sparkles:", "Introduce new features."),
+    gitmoji("📝", ":memo:", "Add or update documentation."),
+    gitmoji("🚀", ":rocket:", "Deploy stuff."),
+    gitmoji("💄", ":lipstick:", "Add or update the UI and style files."),
+    gitmoji("🎉", ":tada:", "Begin a project."),
+    gitmoji("✅", ":white_check_mark:", "Add, update, or pass tests."),
+    gitmoji("🔒️", ":lock:", "Fix security or privacy issues."),
+    gitmoji("🔐", ":closed_lock_with_key:", "Add or update secrets."),
+    gitmoji("🔖", ":bookmark:", "Release / Version tags."),
+    gitmoji("🚨", ":rotating_light:", "Fix compiler / linter warnings."),
+    gitmoji("🚧", ":construction:", "Work in progress."),
+    gitmoji("💚", ":green_heart:", "Fix CI Build."),
+    gitmoji("⬇️", ":arrow_down:", "Downgrade dependencies."),
+    gitmoji("⬆️", ":arrow_up:", "Upgrade dependencies."),
+    gitmoji("📌", ":pushpin:", "Pin dependencies to specific versions."),
+    gitmoji(
+        "👷",
+        ":construction_worker:",
+        "Add or update CI build system.",
+    ),
+    gitmoji(
+        "📈",
+        ":chart_with_upwards_trend:",
+        "Add or update analytics or track code.",
+    ),
+    gitmoji("♻️", ":recycle:", "Refactor code."),
+    gitmoji("➕", ":heavy_plus_sign:", "Add a dependency."),
+    gitmoji("➖", ":heavy_minus_sign:", "Remove a dependency."),
+    gitmoji("🔧", ":wrench:", "Add or update configuration files."),
+    gitmoji("🔨", ":hammer:", "Add or update development scripts."),
+    gitmoji(
+        "🌐",
+        ":globe_with_meridians:",
+        "Internationalization and localization.",
+    ),
+    gitmoji("✏️", ":pencil2:", "Fix typos."),
+    gitmoji("💩", ":poop:", "Write bad code that needs to be improved."),
+    gitmoji("⏪️", ":rewind:", "Revert changes."),
+    gitmoji("🔀", ":twisted_rightwards_arrows:", "Merge branches."),
+    gitmoji(
+        "📦️",
+        ":package:",
+        "Add or update compiled files or packages.",
+    ),
+    gitmoji("👽️", ":alien:", "Update code due to external API changes."),
+    gitmoji(
+        "🚚",
+        ":truck:",
+        "Move or rename resources (e.g.: files, paths, routes).",
+    ),
+    gitmoji("📄", ":page_facing_up:", "Add or update license."),
+    gitmoji("💥", ":boom:", "Introduce breaking changes."),
+    gitmoji("🍱", ":bento:", "Add or update assets."),
+    gitmoji("♿️", ":wheelchair:", "Improve accessibility."),
+    gitmoji("💡", ":bulb:", "Add or update comments in source code."),
+    gitmoji("🍻", ":beers:", "Write code drunkenly."),
+    gitmoji("💬", ":speech_balloon:", "Add or update text and literals."),
+    gitmoji("🗃️", ":card_file_box:", "Perform database related changes."),
+    gitmoji("🔊", ":loud_sound:", "Add or update logs."),
+    gitmoji("🔇", ":mute:", "Remove logs."),
+    gitmoji(
+        "👥",
+        ":busts_in_silhouette:",
+        "Add or update contributor(s).",
+    ),
+    gitmoji(
+        "🚸",
+        ":children_crossing:",
+        "Improve user experience / usability.",
+    ),
+    gitmoji(
+        "🏗️",
+        ":building_construction:",
+        "Make architectural changes.",
+    ),
+    gitmoji("📱", ":iphone:", "Work on responsive design."),
+    gitmoji("🤡", ":clown_face:", "Mock things."),
+    gitmoji("🥚", ":egg:", "Add or update an easter egg."),
+    gitmoji("🙈", ":see_no_evil:", "Add or update a .gitignore file."),
+    gitmoji("📸", ":camera_flash:", "Add or update snapshots."),
+    gitmoji("⚗️", ":alembic:", "Perform experiments."),
+    gitmoji("🔍️", ":mag:", "Improve SEO."),
+    gitmoji("🏷️", ":label:", "Add or update types."),
+    gitmoji("🌱", ":seedling:", "Add or update seed files."),
+    gitmoji(
+        "🚩",
+        ":triangular_flag_on_post:",
+        "Add, update, or remove feature flags.",
+    ),
+    gitmoji("🥅", ":goal_net:", "Catch errors."),
+    gitmoji("💫", ":dizzy:", "Add or update animations and transitions."),
+    gitmoji(
+        "🗑️",
+        ":wastebasket:",
+        "Deprecate code that needs to be cleaned up.",
+    ),
+    gitmoji(
+        "🛂",
+        ":passport_control:",
+        "Work on code related to authorization, roles and permissions.",
+    ),
+    gitmoji(
+        "🩹",
+        ":adhesive_bandage:",
+        "Simple fix for a non-critical issue.",
+    ),
+    gitmoji("🧐", ":monocle_face:", "Data exploration/inspection."),
+    gitmoji("⚰️", ":coffin:", "Remove dead code."),
+    gitmoji("🧪", ":test_tube:", "Add a failing test."),
+    gitmoji("👔", ":necktie:", "Add or update business logic."),
+    gitmoji("🩺", ":stethoscope:", "Add or update healthcheck."),
+    gitmoji("🧱", ":bricks:", "Infrastructure related changes."),
+    gitmoji("🧑‍💻", ":technologist:", "Improve developer experience."),
+    gitmoji(
+        "💸",
+        ":money_with_wings:",
+        "Add sponsorships or money related infrastructure.",
+    ),
+    gitmoji(
+        "🧵",
+        ":thread:",
+        "Add or update code related to multithreading or concurrency.",
+    ),
+    gitmoji(
+        "🦺",
+        ":safety_vest:",
+        "Add or update code related to validation.",
+    ),
+    gitmoji("✈️", ":airplane:", "Improve offline support."),
+    gitmoji("🦖", ":t-rex:", "Code that adds backwards compatibility."),
+];
+
+/// The gitmoji for each of the types convit ships with.
+const TYPES: [(&str, &str); 11] = [
+    ("feat", ":sparkles:"),
+    ("fix", ":bug:"),
+    ("build", ":package:"),
+    ("chore", ":wrench:"),
+    ("ci", ":construction_worker:"),
+    ("docs", ":memo:"),
+    ("style", ":art:"),
+    ("refactor", ":recycle:"),
+    ("revert", ":rewind:"),
+    ("perf", ":zap:"),
+    ("test", ":white_check_mark:"),
+];
+
+/// The gitmoji written as `text`, either its emoji, with or without the
+/// emoji presentation selector, or its shortcode.
+pub fn find(text: &str) -> Option<&'static Gitmoji> {
+    GITMOJIS.iter().find(|gitmoji| {
+        gitmoji.code == text || without_selector(gitmoji.emoji) == without_selector(text)
+    })
+}
+
+/// The gitmoji a header starts with, as it is written there.
+pub fn prefix(header: &str) -> Option<&str> {
+    if let Some(code) = header.strip_prefix(':') {
+        let end = code.find(':')? + 2;
+        return find(&header[..end]).map(|_| &header[..end]);
+    }
+    // Emojis are matched without the selector, which may be left out.
+    let end = GITMOJIS
+        .iter()
+        .map(|gitmoji| without_selector(gitmoji.emoji))
+        .filter(|emoji| header.starts_with(emoji.as_str()))
+        .map(|emoji| emoji.len())
+        .max()?;
+    let end = match header[end..].strip_prefix('\u{fe0f}') {
+        Some(_) => end + '\u{fe0f}'.len_utf8(),
+        None => end,
+    };
+    Some(&header[..end])
+}
+
+/// The gitmoji convit uses for one of its built-in types.
+pub fn for_type(commit_type: &str) -> Option<&'static Gitmoji> {
+    TYPES
+        .iter()
+        .find(|(name, _)| *name == commit_type)
+        .and_then(|(_, code)| find(code))
+}
+
+fn without_selector(emoji: &str) -> String {
+    emoji.replace('\u{fe0f}', "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_gitmojis_by_emoji_or_shortcode() {
+        let zap = find(":zap:").unwrap();
+        assert_eq!(zap.emoji, "\u{26a1}\u{fe0f}");
+        assert_eq!(find("\u{26a1}"), Some(zap));
+        assert_eq!(find("\u{26a1}\u{fe0f}"), Some(zap));
+        assert_eq!(find("✨").map(|g| g.code), Some(":sparkles:"));
+        assert_eq!(find(":not_a_gitmoji:"), None);
+        assert_eq!(find("zap"), None);
+    }
+
+    #[test]
+    fn finds_the_prefix_as_written() {
+        assert_eq!(prefix("\u{26a1}\u{fe0f} perf: x"), Some("\u{26a1}\u{fe0f}"));
+        assert_eq!(prefix("\u{26a1} perf: x"), Some("\u{26a1}"));
+        assert_eq!(prefix("\u{26a1}(db): x"), Some("\u{26a1}"));
+        assert_eq!(prefix(":zap: perf: x"), Some(":zap:"));
+        assert_eq!(prefix(":zap:(db): x"), Some(":zap:"));
+        assert_eq!(prefix(":nope: perf: x"), None);
+        assert_eq!(prefix(":zap"), None);
+        assert_eq!(prefix("perf: x"), None);
+        assert_eq!(prefix(""), None);
+    }
+
+    #[test]
+    fn every_type_has_a_gitmoji() {
+        for (name, _) in TYPES {
+            assert!(for_type(name).is_some(), "{name}");
+        }
+        assert_eq!(for_type("feat").map(|g| g.emoji), Some("✨"));
+        assert_eq!(for_type("wip"), None);
+    }
+}
@@ -0,0 +1,270 @@
+use std::{ops::Range, path::Path};
+
+/// What a piece of source code is, for colouring it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Plain,
+    Keyword,
+    String,
+    Comment,
+    Number,
+}
+
+/// Enough of a language's lexical rules to colour single lines.
+#[derive(Debug)]
+pub struct Syntax {
+    keywords: &'static [&'static str],
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+}
+
+const RUST: Syntax = Syntax {
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    line_comment: Some("//"),
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+};
+
+const C_LIKE: Syntax = Syntax {
+    keywords: &[
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "do",
+        "else",
+        "enum",
+        "extends",
+        "false",
+        "final",
+        "for",
+        "func",
+        "function",
+        "go",
+        "if",
+        "implements",
+        "import",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "nil",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "static",
+        "struct",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "var",
+        "void",
+        "while",
+        "async",
+        "await",
+        "export",
+        "from",
+        "of",
+        "typeof",
+        "undefined",
+        "#include",
+        "#define",
+        "int",
+        "char",
+        "long",
+        "double",
+        "float",
+        "unsigned",
+        "sizeof",
+    ],
+    line_comment: Some("//"),
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+};
+
+const PYTHON: Syntax = Syntax {
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield",
+    ],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+const SHELL: Syntax = Syntax {
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "exit", "export", "fi", "for", "function",
+        "if", "in", "local", "return", "then", "until", "while",
+    ],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+/// TOML, YAML and similar configuration files.
+const CONFIG: Syntax = Syntax {
+    keywords: &["true", "false", "null", "yes", "no"],
+    line_comment: Some("#"),
+    block_comment: None,
+    quotes: &['"', '\''],
+};
+
+const JSON: Syntax = Syntax {
+    keywords: &["true", "false", "null"],
+    line_comment: None,
+    block_comment: None,
+    quotes: &['"'],
+};
+
+/// The syntax of a file, judged by its extension or name.
+pub fn for_path(path: &str) -> Option<&'static Syntax> {
+    let path = Path::new(path);
+    let name = path.file_name()?.to_str()?;
+    if matches!(name, "Makefile" | "Dockerfile" | "Jenkinsfile") {
+        return Some(&SHELL);
+    }
+    match path.extension()?.to_str()? {
+        "rs" => Some(&RUST),
+        "c" | "h" | "cc" | "cpp" | "hpp" | "java" | "kt" | "go" | "js" | "jsx" | "mjs" | "cjs"
+        | "ts" | "tsx" | "cs" | "swift" | "scala" => Some(&C_LIKE),
+        "py" => Some(&PYTHON),
+        "sh" | "bash" | "zsh" | "fish" => Some(&SHELL),
+        "toml" | "yaml" | "yml" | "ini" | "cfg" | "conf" => Some(&CONFIG),
+        "json" => Some(&JSON),
+        _ => None,
+    }
+}
+
+/// Splits one line of code into coloured pieces, as byte ranges.
+///
+/// Lines are coloured on their own, so a block comment only counts on the
+/// line it starts on.
+pub fn highlight(line: &str, syntax: &Syntax) -> Vec<(Range<usize>, Token)> {
+    let mut tokens: Vec<(Range<usize>, Token)> = Vec::new();
+    let mut push = |range: Range<usize>, token: Token| match tokens.last_mut() {
+        Some((last, kind)) if *kind == token && last.end == range.start => last.end = range.end,
+        _ => tokens.push((range, token)),
+    };
+
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        if syntax
+            .line_comment
+            .is_some_and(|start| rest.starts_with(start))
+        {
+            push(i..line.len(), Token::Comment);
+            break;
+        }
+        if let Some((start, end)) = syntax
+            .block_comment
+            .filter(|(start, _)| rest.starts_with(start))
+        {
+            let close = rest[start.len()..]
+                .find(end)
+                .map_or(line.len(), |p| i + start.len() + p + end.len());
+            push(i..close, Token::Comment);
+            i = close;
+        } else if syntax.quotes.contains(&c) {
+            let close = string_end(rest, c).map_or(line.len(), |p| i + p);
+            push(i..close, Token::String);
+            i = close;
+        } else if c.is_alphanumeric() || c == '_' || c == '#' {
+            // Decimal points belong to numbers only.
+            let number = c.is_ascii_digit();
+            let len = rest
+                .find(|c: char| {
+                    !(c.is_alphanumeric() || c == '_' || c == '#' || number && c == '.')
+                })
+                .unwrap_or(rest.len());
+            let word = rest[..len].trim_end_matches('.');
+            let token = if number {
+                Token::Number
+            } else if syntax.keywords.contains(&word) {
+                Token::Keyword
+            } else {
+                Token::Plain
+            };
+            push(i..i + word.len().max(c.len_utf8()), token);
+            i += word.len().max(c.len_utf8());
+        } else {
+            push(i..i + c.len_utf8(), Token::Plain);
+            i += c.len_utf8();
+        }
+    }
+    tokens
+}
+
+/// The end of the string opened by the `quote` that `text` starts with,
+/// skipping escaped quotes.
+fn string_end(text: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (i, c) in text.char_indices().skip(1) {
+        match c {
+            '\\' if !escaped => escaped = true,
+            c if c == quote && !escaped => return Some(i + c.len_utf8()),
+            _ => escaped = false,
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens<'a>(line: &'a str, path: &str) -> Vec<(&'a str, Token)> {
+        highlight(line, for_path(path).unwrap())
+            .into_iter()
+            .map(|(range, token)| (&line[range], token))
+            .collect()
+    }
+
+    #[test]
+    fn colours_keywords_strings_numbers_and_comments() {
+        assert_eq!(
+            tokens(r#"let s = "a \" b"; // 1.5"#, "src/main.rs"),
+            [
+                ("let", Token::Keyword),
+                (" s = ", Token::Plain),
+                (r#""a \" b""#, Token::String),
+                ("; ", Token::Plain),
+                ("// 1.5", Token::Comment),
+            ]
+        );
+        assert_eq!(
+            tokens("x = 1.5.", "a.py"),
+            [
+                ("x = ", Token::Plain),
+                ("1.5", Token::Number),
+                (".", Token::Plain)
+            ]
+        );
+    }
+
+    #[test]
+    fn picks_syntax_by_file_name() {
+        assert!(for_path("Makefile").is_some());
+        assert!(for_path("web/app.tsx").is_some());
+        assert!(for_path("notes.txt").is_none());
+        assert!(for_path("LICENSE").is_none());
+    }
+}
@@ -0,0 +1,323 @@
+use std::{
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::git::{self, GitError};
+
+const BEGIN_MARKER: &str = "# >>> convit >>>";
+const END_MARKER: &str = "# <<< convit <<<";
+
+/// The hooks convit manages and the command each one runs.
+const HOOKS: [(&str, &str); 2] = [
+    ("commit-msg", r#"convit lint "$1" || exit 1"#),
+    (
+        "prepare-commit-msg",
+        r#"convit template "$1" "$2" || exit 1"#,
+    ),
+];
+
+/// How to treat hooks that exist but weren't written by convit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Existing {
+    /// Leave them alone and fail.
+    #[default]
+    Refuse,
+    /// Add convit's block to the existing shell script, right after its
+    /// `#!` line so it runs before a closing `exec` or `exit`.
+    Chain,
+    /// Replace them.
+    Overwrite,
+}
+
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error(transparent)]
+    Git(#[from] GitError),
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{} already exists, use --chain to run convit ahead of it or --force to replace it", .0.display())]
+    Exists(PathBuf),
+    #[error("{} isn't a shell script convit can be chained into, use --force to replace it", .0.display())]
+    NotShell(PathBuf),
+}
+
+/// Writes the `commit-msg` and `prepare-commit-msg` hooks, returning their paths.
+///
+/// Convit's lines are wrapped in marker comments so reinstalling replaces
+/// them in place and [`uninstall`] can remove them from chained scripts.
+pub fn install(existing: Existing) -> Result<Vec<PathBuf>, HookError> {
+    install_in(&git::hooks_dir()?, existing)
+}
+
+fn install_in(dir: &Path, existing: Existing) -> Result<Vec<PathBuf>, HookError> {
+    fs::create_dir_all(dir).map_err(|source| HookError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    // Check every hook first so a refusal doesn't leave a half-installed set.
+    let mut scripts = Vec::new();
+    for (name, command) in HOOKS {
+        let path = dir.join(name);
+        let block = format!("{BEGIN_MARKER}\n{command}\n{END_MARKER}\n");
+        let script = match read(&path)? {
+            None => format!("#!/bin/sh\n{block}"),
+            Some(current) => match block_range(&current) {
+                Some(range) => {
+                    let mut script = current;
+                    script.replace_range(range, &block);
+                    script
+                }
+                None => match existing {
+                    Existing::Refuse => return Err(HookError::Exists(path)),
+                    Existing::Chain if is_shell(&current) => chain(&current, &block),
+                    Existing::Chain => return Err(HookError::NotShell(path)),
+                    Existing::Overwrite => format!("#!/bin/sh\n{block}"),
+                },
+            },
+        };
+        scripts.push((path, script));
+    }
+
+    for (path, script) in &scripts {
+        write_executable(path, script)?;
+    }
+    Ok(scripts.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Removes convit's lines from the hooks, deleting scripts that have nothing
+/// else left in them. Returns the paths of the hooks that were changed.
+pub fn uninstall() -> Result<Vec<PathBuf>, HookError> {
+    uninstall_in(&git::hooks_dir()?)
+}
+
+fn uninstall_in(dir: &Path) -> Result<Vec<PathBuf>, HookError> {
+    let mut changed = Vec::new();
+
+    for (name, _) in HOOKS {
+        let path = dir.join(name);
+        let Some(rest) = read(&path)?.as_deref().and_then(remove_block) else {
+            continue;
+        };
+        let result = if rest
+            .lines()
+            .all(|line| line.is_empty() || line.starts_with("#!"))
+        {
+            fs::remove_file(&path)
+        } else {
+            fs::write(&path, format!("{}\n", rest.trim_end()))
+        };
+        result.map_err(|source| HookError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        changed.push(path);
+    }
+    Ok(changed)
+}
+
+fn read(path: &Path) -> Result<Option<String>, HookError> {
+    match fs::read_to_string(path) {
+        Ok(script) => Ok(Some(script)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(HookError::Io {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+/// Whether git runs a hook with a shell convit's block works in. Scripts
+/// without a `#!` line are run with `sh`.
+fn is_shell(script: &str) -> bool {
+    let Some(line) = script
+        .lines()
+        .next()
+        .and_then(|line| line.strip_prefix("#!"))
+    else {
+        return true;
+    };
+    let mut words = line.split_whitespace();
+    let program = match words.next() {
+        Some(env) if env.ends_with("/env") => words.find(|word| !word.starts_with('-')),
+        program => program,
+    };
+    program
+        .and_then(|program| program.rsplit('/').next())
+        .is_some_and(|name| matches!(name, "sh" | "bash" | "dash" | "ksh" | "zsh"))
+}
+
+/// The script with convit's block after its `#!` line, ahead of its own
+/// commands.
+fn chain(script: &str, block: &str) -> String {
+    match script.strip_prefix("#!") {
+        Some(_) => {
+            let (shebang, body) = script.split_once('\n').unwrap_or((script, ""));
+            format!("{shebang}\n{block}\n{body}")
+        }
+        None => format!("{block}\n{script}"),
+    }
+}
+
+/// Where convit's block is in the script, with its line break.
+fn block_range(script: &str) -> Option<Range<usize>> {
+    let start = script.find(BEGIN_MARKER)?;
+    let mut end = script[start..].find(END_MARKER)? + start + END_MARKER.len();
+    if script[end..].starts_with('\n') {
+        end += 1;
+    }
+    Some(start..end)
+}
+
+/// The script without convit's block and the blank line [`chain`] puts
+/// after it, or `None` if it doesn't have one.
+fn remove_block(script: &str) -> Option<String> {
+    let range = block_range(script)?;
+    let (before, mut after) = (&script[..range.start], &script[range.end..]);
+    if before.is_empty() || before.ends_with('\n') {
+        after = after.strip_prefix('\n').unwrap_or(after);
+    }
+    Some(format!("{before}{after}"))
+}
+
+fn write_executable(path: &Path, script: &str) -> Result<(), HookError> {
+    let io_error = |source| HookError::Io {
+        path: path.to_path_buf(),
+        source,
+    };
+    fs::write(path, script).map_err(io_error)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755)).map_err(io_error)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "#!/bin/sh\nbefore\n# >>> convit >>>\nold\n# <<< convit <<<\nafter\n";
+
+    #[test]
+    fn finds_the_block_between_other_commands() {
+        let range = block_range(SCRIPT).unwrap();
+        assert_eq!(&SCRIPT[range], "# >>> convit >>>\nold\n# <<< convit <<<\n");
+        assert_eq!(block_range("#!/bin/sh\nother\n"), None);
+    }
+
+    #[test]
+    fn removes_only_the_block() {
+        assert_eq!(
+            remove_block(SCRIPT).as_deref(),
+            Some("#!/bin/sh\nbefore\nafter\n")
+        );
+    }
+
+    /// A scratch hooks directory with the given hooks, removed again on drop.
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str, hooks: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!("convit-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            for (hook, script) in hooks {
+                fs::write(dir.join(hook), script).unwrap();
+            }
+            Fixture(dir)
+        }
+
+        fn read(&self, hook: &str) -> Option<String> {
+            fs::read_to_string(self.0.join(hook)).ok()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    const PRE_COMMIT: &str = "#!/usr/bin/env bash\n# managed\n\nexec pre-commit hook-impl \"$@\"\n";
+
+    #[test]
+    fn installs_new_hooks() {
+        let hooks = Fixture::new("hooks-new", &[]);
+        assert_eq!(install_in(&hooks.0, Existing::Refuse).unwrap().len(), 2);
+        assert_eq!(
+            hooks.read("commit-msg").as_deref(),
+            Some("#!/bin/sh\n# >>> convit >>>\nconvit lint \"$1\" || exit 1\n# <<< convit <<<\n")
+        );
+        // Reinstalling leaves them as they are.
+        install_in(&hooks.0, Existing::Refuse).unwrap();
+        assert_eq!(
+            hooks.read("commit-msg").as_deref(),
+            Some("#!/bin/sh\n# >>> convit >>>\nconvit lint \"$1\" || exit 1\n# <<< convit <<<\n")
+        );
+
+        assert_eq!(uninstall_in(&hooks.0).unwrap().len(), 2);
+        assert_eq!(hooks.read("commit-msg"), None);
+        assert_eq!(hooks.read("prepare-commit-msg"), None);
+    }
+
+    #[test]
+    fn refuses_other_hooks_without_installing_any() {
+        let hooks = Fixture::new("hooks-refuse", &[("commit-msg", PRE_COMMIT)]);
+        assert!(matches!(
+            install_in(&hooks.0, Existing::Refuse),
+            Err(HookError::Exists(path)) if path.ends_with("commit-msg")
+        ));
+        assert_eq!(hooks.read("commit-msg").as_deref(), Some(PRE_COMMIT));
+        assert_eq!(hooks.read("prepare-commit-msg"), None);
+    }
+
+    #[test]
+    fn chains_before_the_existing_commands() {
+        let hooks = Fixture::new("hooks-chain", &[("commit-msg", PRE_COMMIT)]);
+        install_in(&hooks.0, Existing::Chain).unwrap();
+        let chained = "#!/usr/bin/env bash\n# >>> convit >>>\nconvit lint \"$1\" || exit 1\n# <<< convit <<<\n\n# managed\n\nexec pre-commit hook-impl \"$@\"\n";
+        assert_eq!(hooks.read("commit-msg").as_deref(), Some(chained));
+
+        // The block is replaced where it is.
+        install_in(&hooks.0, Existing::Refuse).unwrap();
+        assert_eq!(hooks.read("commit-msg").as_deref(), Some(chained));
+
+        uninstall_in(&hooks.0).unwrap();
+        assert_eq!(hooks.read("commit-msg").as_deref(), Some(PRE_COMMIT));
+        assert_eq!(hooks.read("prepare-commit-msg"), None);
+    }
+
+    #[test]
+    fn chains_only_into_shell_scripts() {
+        let python = "#!/usr/bin/env python3\nimport sys\n";
+        let hooks = Fixture::new("hooks-python", &[("commit-msg", python)]);
+        assert!(matches!(
+            install_in(&hooks.0, Existing::Chain),
+            Err(HookError::NotShell(_))
+        ));
+        assert_eq!(hooks.read("commit-msg").as_deref(), Some(python));
+
+        assert!(is_shell("#!/bin/sh -e\n"));
+        assert!(is_shell("#!/usr/bin/env -S bash -e\n"));
+        assert!(is_shell("exit 0\n"));
+        assert!(!is_shell("#!/usr/bin/node\n"));
+        assert_eq!(chain("exit 0\n", "block\n"), "block\n\nexit 0\n");
+        assert_eq!(chain("#!/bin/sh", "block\n"), "#!/bin/sh\nblock\n\n");
+    }
+
+    #[test]
+    fn overwrites_other_hooks() {
+        let hooks = Fixture::new("hooks-force", &[("commit-msg", PRE_COMMIT)]);
+        install_in(&hooks.0, Existing::Overwrite).unwrap();
+        let script = hooks.read("commit-msg").unwrap();
+        assert!(script.starts_with("#!/bin/sh\n# >>> convit >>>\n"));
+        assert!(!script.contains("pre-commit"));
+    }
+}
@@ -1,6 +1,48 @@
 /// Application.
 pub mod app;
 
+/// Ticket references in branch names.
+pub mod branch;
+
+/// Changelogs from conventional history.
+pub mod changelog;
+
+/// Conventional commit model and parser.
+pub mod commit;
+
+/// Importing commitlint configuration.
+pub mod commitlint;
+
+/// Fuzzy completion of footer values.
+pub mod complete;
+
+/// Commit types, scopes and footers from config files.
+pub mod config;
+
+/// Parsing unified diffs.
+pub mod diff;
+
+/// Git integration.
+pub mod git;
+
+/// The gitmoji catalog and gitmoji headers.
+pub mod gitmoji;
+
+/// Syntax highlighting for lines of code in diffs.
+pub mod highlight;
+
+/// Git hook installation.
+pub mod hooks;
+
+/// Commit message validation.
+pub mod lint;
+
+/// Version fields in package manifests.
+pub mod manifest;
+
+/// Commit policy rules.
+pub mod rules;
+
 // /// Terminal events handler.
 // pub mod event;
 
@@ -10,5 +52,17 @@ pub mod app;
 // /// Terminal user interface.
 // pub mod tui;
 
+/// Commit type and scope suggestions from the staged changes.
+pub mod suggest;
+
+/// Commit body templates.
+pub mod template;
+
+/// Issue tracker reference syntax.
+pub mod tracker;
+
+/// Semantic versions and release recommendations.
+pub mod version;
+
 /// Widget renderer.
 pub mod ui;
\ No newline at end of file
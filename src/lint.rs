@@ -0,0 +1,115 @@
+use crate::{
+    config::Config,
+    git,
+    rules::{self, Diagnostic},
+};
+
+/// Headers git writes itself for merges, reverts and autosquash commits.
+/// They aren't conventional, so they're let through as commitlint does.
+const GENERATED: [&str; 10] = [
+    "Merge branch ",
+    "Merge branches ",
+    "Merge remote-tracking branch ",
+    "Merge tag ",
+    "Merge commit ",
+    "Revert \"",
+    "Reapply \"",
+    "fixup! ",
+    "squash! ",
+    "amend! ",
+];
+
+/// Checks a commit message file against the rules in the config.
+///
+/// Comment lines and the scissors section are ignored, but positions refer
+/// to lines of the original file. Messages git generated are skipped.
+pub fn lint(text: &str, comment_char: char, config: &Config) -> Vec<Diagnostic> {
+    let lines: Vec<(usize, &str)> = git::message_lines(text, comment_char)
+        .into_iter()
+        .skip_while(|(_, line)| line.trim().is_empty())
+        .collect();
+    if lines
+        .first()
+        .is_some_and(|(_, header)| is_generated(header))
+    {
+        return Vec::new();
+    }
+    let message = lines
+        .iter()
+        .map(|(_, line)| *line)
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut diagnostics = rules::check_message(&message, config);
+    for diagnostic in &mut diagnostics {
+        diagnostic.line = lines
+            .get(diagnostic.line - 1)
+            .map_or(diagnostic.line, |(number, _)| *number);
+    }
+    diagnostics
+}
+
+fn is_generated(header: &str) -> bool {
+    GENERATED.iter().any(|prefix| header.starts_with(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config
+            .merge_json(r#"{"types": [{"name": "feat"}, {"name": "fix"}]}"#)
+            .unwrap();
+        config
+    }
+
+    #[test]
+    fn skips_merge_messages() {
+        for message in [
+            "Merge branch 'topic'\n",
+            "Merge branch 'topic' into main\n",
+            "Merge branches 'a' and 'b'\n",
+            "Merge remote-tracking branch 'origin/main'\n",
+            "Merge tag 'v1.0.0'\n",
+            "Merge commit 'abc1234'\n",
+        ] {
+            assert_eq!(lint(message, '#', &config()), Vec::new(), "{message}");
+        }
+    }
+
+    #[test]
+    fn skips_revert_messages() {
+        let message = "Revert \"feat: add arrays\"\n\nThis reverts commit abc1234.\n";
+        assert_eq!(lint(message, '#', &config()), Vec::new());
+        let message = "Reapply \"feat: add arrays\"\n\nThis reverts commit def5678.\n";
+        assert_eq!(lint(message, '#', &config()), Vec::new());
+    }
+
+    #[test]
+    fn skips_fixup_squash_and_amend_messages() {
+        for message in [
+            "fixup! feat: add arrays\n",
+            "squash! feat: add arrays\n\nMore changes.\n",
+            "amend! feat: add arrays\n\nfeat: add arrays and maps\n",
+        ] {
+            assert_eq!(lint(message, '#', &config()), Vec::new(), "{message}");
+        }
+    }
+
+    #[test]
+    fn still_checks_lookalikes() {
+        for message in ["Merged the branch\n", "revert something\n", "fixup: x\n"] {
+            assert!(!lint(message, '#', &config()).is_empty(), "{message}");
+        }
+    }
+
+    #[test]
+    fn maps_positions_to_file_lines() {
+        let text = "# comment\n\nwip: x\n";
+        let diagnostics = lint(text, '#', &config());
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics.iter().all(|d| d.line == 3));
+    }
+}
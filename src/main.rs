@@ -3,30 +3,69 @@
 use std::{
     collections::HashMap,
     error::Error,
+    ffi::OsString,
+    fs,
     io::{self, stdout},
+    ops::Range,
+    path::PathBuf,
+    process,
 };
 
 use color_eyre::config::HookBuilder;
+use convit::{
+    branch::{self, Ticket},
+    changelog::Changelog,
+    commit::{CommitStatus, ConventionalCommit, Footer, HeaderGitmoji, Separator},
+    complete,
+    config::{Config, FooterConfig, FooterValue, TypeConfig},
+    diff::{self, FileDiff},
+    git::{self, CommitOptions, GitError, StatusEntry},
+    gitmoji::{self, Gitmoji, GITMOJIS},
+    highlight::{self, Syntax, Token},
+    hooks::{self, Existing},
+    lint,
+    manifest::{self, Package},
+    rules::{self, Diagnostic, Severity},
+    suggest::{self, Suggestion},
+    template::{self, Placeholders},
+    tracker,
+    version::{self, Version},
+};
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEventKind},
+    event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     ExecutableCommand,
 };
 use ratatui::{prelude::*, style::palette::tailwind, widgets::*};
-use tui_textarea::{Input, Key, TextArea};
+use tui_textarea::{CursorMove, TextArea};
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CurrentScreen {
     Main,
     Editing,
+    /// Choosing `git commit` options before committing.
+    Confirming,
+    /// Staging hunks and lines of one file.
+    Diff,
+    /// Reading everything that is staged.
+    StagedDiff,
+    /// Typing the value of a footer.
+    FooterValue,
+    /// Searching the gitmoji catalog.
+    Gitmoji,
     Exiting,
 }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CurrentlyEditing {
     CommitType,
     CommitScope,
     CommitDescription,
     CommitBody,
     CommitFooters,
+    /// The footers added to the commit, below the footer list.
+    PickedFooters,
+    /// The staged-files panel.
+    CommitFiles,
 }
 
 // Users should be able to select from a list of commit types,
@@ -49,7 +88,7 @@ pub enum CommitType {
     Test,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum CommitFooters {
     BreakingChange,
     SignedOffBy,
@@ -60,192 +99,192 @@ pub enum CommitFooters {
     Fixes,
     Cc,
     ReviewedBy,
+    CoAuthoredBy,
 }
 
 impl CommitFooters {
-    fn to_list_item(&self, index: usize) -> ListItem {
-        let bg_color = NORMAL_ROW_COLOR;
+    fn all() -> [CommitFooters; 10] {
+        [
+            CommitFooters::BreakingChange,
+            CommitFooters::SignedOffBy,
+            CommitFooters::AckedBy,
+            CommitFooters::HelpedBy,
+            CommitFooters::ReferenceTo,
+            CommitFooters::SeeAlso,
+            CommitFooters::Fixes,
+            CommitFooters::Cc,
+            CommitFooters::ReviewedBy,
+            CommitFooters::CoAuthoredBy,
+        ]
+    }
 
-        let line = match self {
-            &CommitFooters::BreakingChange => Line::styled(
-                format!("{:?}: Use when making changes to patch a bug.", self),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::SignedOffBy => Line::styled(
-                format!("{:?}: Use when adding a new feature.", self),
-                (COMPLETED_TEXT_COLOR, bg_color),
-            ),
-            &CommitFooters::AckedBy => Line::styled(
-                format!(
-                    "{:?}: Use when changing the build system or external dependencies.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::HelpedBy => Line::styled(
-                format!(
-                    "{:?}: Use when making non-functional changes that don't concern the codebase.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::ReferenceTo => Line::styled(
-                format!(
-                    "{:?}: Use when changing CI configurations or scripts.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::SeeAlso => Line::styled(
-                format!("{:?}: Use when making changes to documentation.", self),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::Fixes => Line::styled(
-                format!(
-                    "{:?}: Use when making non-semantic changes, such as formatting.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::Cc => Line::styled(
-                format!(
-                    "{:?}: Use when making changes that don't fix a bug or add a feature.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitFooters::ReviewedBy => Line::styled(
-                format!("{:?}: Use when reverting a previous/prior commit.", self),
-                TEXT_COLOR,
-            ),
-        };
+    /// The trailer token written into the commit message.
+    fn token(&self) -> &'static str {
+        match *self {
+            CommitFooters::BreakingChange => "BREAKING CHANGE",
+            CommitFooters::SignedOffBy => "Signed-off-by",
+            CommitFooters::AckedBy => "Acked-by",
+            CommitFooters::HelpedBy => "Helped-by",
+            CommitFooters::ReferenceTo => "Refs",
+            CommitFooters::SeeAlso => "See-also",
+            CommitFooters::Fixes => "Fixes",
+            CommitFooters::Cc => "Cc",
+            CommitFooters::ReviewedBy => "Reviewed-by",
+            CommitFooters::CoAuthoredBy => "Co-authored-by",
+        }
+    }
+
+    /// Help text shown next to the token.
+    fn description(&self) -> &'static str {
+        match *self {
+            CommitFooters::BreakingChange => "What breaks and how to migrate.",
+            CommitFooters::SignedOffBy => "Certifies you may submit the change, as Name <email>.",
+            CommitFooters::AckedBy => {
+                "A maintainer of the affected code approved it, as Name <email>."
+            }
+            CommitFooters::HelpedBy => "Credits someone who helped, as Name <email>.",
+            CommitFooters::ReferenceTo => "Related issues or commits, e.g. #123 or PROJ-123.",
+            CommitFooters::SeeAlso => "Related commits, issues or links.",
+            CommitFooters::Fixes => "Issues this change fixes, e.g. #123 or PROJ-123.",
+            CommitFooters::Cc => "Someone who should know about the change, as Name <email>.",
+            CommitFooters::ReviewedBy => "Someone who reviewed the change, as Name <email>.",
+            CommitFooters::CoAuthoredBy => {
+                "Someone who wrote the change with you, as Name <email>."
+            }
+        }
+    }
 
-        ListItem::new(line).bg(bg_color)
+    /// The form the footer's value must take.
+    fn value(&self) -> FooterValue {
+        match *self {
+            CommitFooters::BreakingChange => FooterValue::Text,
+            CommitFooters::SignedOffBy
+            | CommitFooters::AckedBy
+            | CommitFooters::HelpedBy
+            | CommitFooters::Cc
+            | CommitFooters::ReviewedBy
+            | CommitFooters::CoAuthoredBy => FooterValue::Person,
+            CommitFooters::ReferenceTo | CommitFooters::SeeAlso | CommitFooters::Fixes => {
+                FooterValue::Reference
+            }
+        }
     }
 }
 
 impl CommitType {
-    fn to_list_item(&self, index: usize) -> ListItem {
-        let bg_color = NORMAL_ROW_COLOR;
-
-        let line = match self {
-            &CommitType::Fix => Line::styled(
-                format!("{:?}: Use when making changes to patch a bug.", self),
-                TEXT_COLOR,
-            ),
-            &CommitType::Feat => Line::styled(
-                format!("{:?}: Use when adding a new feature.", self),
-                (COMPLETED_TEXT_COLOR, bg_color),
-            ),
-            &CommitType::Build => Line::styled(
-                format!(
-                    "{:?}: Use when changing the build system or external dependencies.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitType::Chore => Line::styled(
-                format!(
-                    "{:?}: Use when making non-functional changes that don't concern the codebase.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitType::Ci => Line::styled(
-                format!(
-                    "{:?}: Use when changing CI configurations or scripts.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitType::Docs => Line::styled(
-                format!("{:?}: Use when making changes to documentation.", self),
-                TEXT_COLOR,
-            ),
-            &CommitType::Style => Line::styled(
-                format!(
-                    "{:?}: Use when making non-semantic changes, such as formatting.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitType::Refactor => Line::styled(
-                format!(
-                    "{:?}: Use when making changes that don't fix a bug or add a feature.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitType::Revert => Line::styled(
-                format!("{:?}: Use when reverting a previous/prior commit.", self),
-                TEXT_COLOR,
-            ),
-            &CommitType::Perf => Line::styled(
-                format!(
-                    "{:?}: Use when making changes to improve performance.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-            &CommitType::Test => Line::styled(
-                format!(
-                    "{:?}: Use when adding tests or editing existing ones.",
-                    self
-                ),
-                TEXT_COLOR,
-            ),
-        };
-
-        ListItem::new(line).bg(bg_color)
+    fn all() -> [CommitType; 11] {
+        [
+            CommitType::Fix,
+            CommitType::Feat,
+            CommitType::Build,
+            CommitType::Chore,
+            CommitType::Ci,
+            CommitType::Docs,
+            CommitType::Style,
+            CommitType::Refactor,
+            CommitType::Revert,
+            CommitType::Perf,
+            CommitType::Test,
+        ]
     }
-}
 
-#[derive(Debug, Clone)]
-pub enum CommitFooter {
-    BreakingChange,
-}
+    /// The type as it appears in the commit header.
+    fn as_str(&self) -> &'static str {
+        match *self {
+            CommitType::Fix => "fix",
+            CommitType::Feat => "feat",
+            CommitType::Build => "build",
+            CommitType::Chore => "chore",
+            CommitType::Ci => "ci",
+            CommitType::Docs => "docs",
+            CommitType::Style => "style",
+            CommitType::Refactor => "refactor",
+            CommitType::Revert => "revert",
+            CommitType::Perf => "perf",
+            CommitType::Test => "test",
+        }
+    }
 
-impl<'a> Default for ConventionalCommit<'a> {
-    fn default() -> Self {
-        Self {
-            commit_type: "",
-            scope: None,
-            description: "",
-            body: None,
-            footers: None,
-            commit_status: CommitStatus::Unready,
+    fn description(&self) -> &'static str {
+        match *self {
+            CommitType::Fix => "Use when making changes to patch a bug.",
+            CommitType::Feat => "Use when adding a new feature.",
+            CommitType::Build => "Use when changing the build system or external dependencies.",
+            CommitType::Chore => {
+                "Use when making non-functional changes that don't concern the codebase."
+            }
+            CommitType::Ci => "Use when changing CI configurations or scripts.",
+            CommitType::Docs => "Use when making changes to documentation.",
+            CommitType::Style => "Use when making non-semantic changes, such as formatting.",
+            CommitType::Refactor => {
+                "Use when making changes that don't fix a bug or add a feature."
+            }
+            CommitType::Revert => "Use when reverting a previous/prior commit.",
+            CommitType::Perf => "Use when making changes to improve performance.",
+            CommitType::Test => "Use when adding tests or editing existing ones.",
         }
     }
 }
 
-impl<'a> ConventionalCommit<'a> {
-    fn new() -> Self {
-        ConventionalCommit::default()
+/// The built-in commit types and footers that config files are merged over.
+fn default_config() -> Config {
+    Config {
+        types: CommitType::all()
+            .iter()
+            .map(|commit_type| TypeConfig {
+                name: commit_type.as_str().to_string(),
+                description: commit_type.description().to_string(),
+                required_footers: Vec::new(),
+                gitmoji: None,
+            })
+            .collect(),
+        footers: CommitFooters::all()
+            .iter()
+            .map(|footer| FooterConfig {
+                token: footer.token().to_string(),
+                description: footer.description().to_string(),
+                value: footer.value(),
+            })
+            .collect(),
+        branch_patterns: branch::default_patterns(),
+        ..Config::default()
     }
 }
-#[derive(Debug, Clone)]
-pub struct Convit<'a> {
-    pub conventional_commit: ConventionalCommit<'a>,
-    pub commit_type_input: &'a str,
-    pub commit_scope: Option<&'a str>,
-    pub commit_description: &'a str,
-    pub commit_body: Option<&'a str>,
-    pub commit_footers: Option<HashMap<CommitFooter, &'a str>>,
-    pub currently_editing: Option<CurrentlyEditing>,
-}
 
-impl<'a> Default for Convit<'a> {
-    fn default() -> Self {
-        Self {
-            conventional_commit: ConventionalCommit::default(),
-            commit_type_input: "",
-            commit_scope: None,
-            commit_description: "",
-            commit_body: None,
-            commit_footers: None,
-            currently_editing: None,
-        }
+/// A row of the commit type or footer list, alternating the background color.
+/// Footer rows pass how often they were added to get a counter.
+fn to_list_item<'a>(
+    name: &str,
+    description: &str,
+    index: usize,
+    picked: Option<usize>,
+) -> ListItem<'a> {
+    let bg_color = match index % 2 {
+        0 => NORMAL_ROW_COLOR,
+        _ => ALT_ROW_COLOR,
+    };
+    let mut line = Line::styled(format!("{name}: {description}"), TEXT_COLOR);
+    if let Some(picked) = picked {
+        let marker = match picked {
+            0 => "[ ] ".to_string(),
+            n => format!("[{n}] "),
+        };
+        line.spans.insert(0, Span::styled(marker, COMPLETED_TEXT_COLOR));
     }
+
+    ListItem::new(line).bg(bg_color)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Convit {
+    pub conventional_commit: ConventionalCommit,
+    pub commit_type_input: String,
+    pub commit_scope: Option<String>,
+    pub commit_description: String,
+    pub commit_body: Option<String>,
+    /// The `!` marker in the header.
+    pub commit_breaking: bool,
+    pub currently_editing: Option<CurrentlyEditing>,
 }
 
 const TODO_HEADER_BG: Color = tailwind::BLUE.c950;
@@ -254,56 +293,179 @@ const ALT_ROW_COLOR: Color = tailwind::SLATE.c900;
 const SELECTED_STYLE_FG: Color = tailwind::BLUE.c300;
 const TEXT_COLOR: Color = tailwind::SLATE.c200;
 const COMPLETED_TEXT_COLOR: Color = tailwind::GREEN.c500;
+const PLACEHOLDER_TEXT_COLOR: Color = tailwind::SLATE.c500;
+const FOOTER_TEXT_COLOR: Color = tailwind::AMBER.c300;
+const BREAKING_TEXT_COLOR: Color = tailwind::RED.c400;
+const HUNK_HEADER_COLOR: Color = tailwind::CYAN.c400;
+const ADDED_BG: Color = tailwind::GREEN.c950;
+const REMOVED_BG: Color = tailwind::RED.c950;
+const ADDED_WORD_BG: Color = tailwind::GREEN.c800;
+const REMOVED_WORD_BG: Color = tailwind::RED.c800;
+const KEYWORD_COLOR: Color = tailwind::VIOLET.c300;
+const NUMBER_COLOR: Color = tailwind::ORANGE.c300;
 
-#[derive(Copy, Clone, Debug)]
-pub enum CommitStatus {
-    Ready,
-    Unready,
-}
-
-#[derive(Debug, Clone)]
-pub struct ConventionalCommit<'a> {
-    pub commit_type: &'a str,
-    pub scope: Option<&'a str>,
-    pub description: &'a str,
-    pub body: Option<&'a str>,
-    pub footers: Option<Vec<&'a str>>,
-    pub commit_status: CommitStatus,
-}
 #[derive(Debug, Clone)]
-struct StatefulList<'a> {
+struct StatefulList {
     state: ListState,
-    commit: ConventionalCommit<'a>,
-    items: Vec<CommitType>,
+    items: Vec<TypeConfig>,
     last_selected: Option<usize>,
 }
 
 #[derive(Debug, Clone)]
-struct StatefulFooterList<'a> {
+struct StatefulFooterList {
     state: ListState,
-    commit: ConventionalCommit<'a>,
-    items: Vec<CommitFooters>,
+    items: Vec<FooterConfig>,
+    // Footers included in the commit, in the order they were picked.
+    // Tokens can repeat.
+    picked: Vec<Footer>,
+    picked_state: ListState,
     last_selected: Option<usize>,
 }
-impl<'a> StatefulFooterList<'a> {
-    fn footer_items(items: Vec<CommitFooters>) -> StatefulFooterList<'a> {
+impl StatefulFooterList {
+    fn footer_items(items: Vec<FooterConfig>) -> StatefulFooterList {
         StatefulFooterList {
             state: ListState::default(),
-            commit: ConventionalCommit::new(),
             items,
+            picked: Vec::new(),
+            picked_state: ListState::default(),
             last_selected: None,
         }
     }
-    fn list_footer_items(items: Vec<CommitFooters>) -> StatefulFooterList<'a> {
-        StatefulFooterList {
-            state: ListState::default(),
-            commit: ConventionalCommit::new(),
-            items,
-            last_selected: None,
+
+    /// How many footers of the kind at `index` were added.
+    fn picked_count(&self, index: usize) -> usize {
+        let footer = &self.items[index];
+        self.picked
+            .iter()
+            .filter(|picked| footer.matches(picked))
+            .count()
+    }
+
+    /// Saves a footer value, replacing the footer at `index` or adding
+    /// a new one after the others.
+    fn save(&mut self, footer: Footer, index: Option<usize>) {
+        match index.and_then(|i| self.picked.get_mut(i)) {
+            Some(picked) => *picked = footer,
+            None => {
+                self.picked.push(footer);
+                self.picked_state.select(Some(self.picked.len() - 1));
+            }
+        }
+    }
+
+    /// Removes the selected added footer.
+    fn remove_picked(&mut self) {
+        let Some(i) = self
+            .picked_state
+            .selected()
+            .filter(|i| *i < self.picked.len())
+        else {
+            return;
+        };
+        self.picked.remove(i);
+        self.picked_state
+            .select(self.picked.len().checked_sub(1).map(|last| i.min(last)));
+    }
+
+    fn next_picked(&mut self) {
+        if self.picked.is_empty() {
+            return;
+        }
+        let i = match self.picked_state.selected() {
+            Some(i) if i + 1 < self.picked.len() => i + 1,
+            _ => 0,
+        };
+        self.picked_state.select(Some(i));
+    }
+
+    fn previous_picked(&mut self) {
+        if self.picked.is_empty() {
+            return;
+        }
+        let i = match self.picked_state.selected() {
+            Some(0) | None => self.picked.len() - 1,
+            Some(i) => i - 1,
+        };
+        self.picked_state.select(Some(i));
+    }
+
+    fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) => {
+                if i >= self.items.len() - 1 {
+                    0
+                } else {
+                    i + 1
+                }
+            }
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
+        };
+        self.state.select(Some(i));
+    }
+
+    fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
+        let i = match self.state.selected() {
+            Some(i) => {
+                if i == 0 {
+                    self.items.len() - 1
+                } else {
+                    i - 1
+                }
+            }
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
+        };
+        self.state.select(Some(i));
+    }
+
+    fn unselect(&mut self) {
+        let offset = self.state.offset();
+        self.last_selected = self.state.selected();
+        self.state.select(None);
+        *self.state.offset_mut() = offset;
+    }
+}
+/// Changed files in the working tree, staged or not.
+#[derive(Debug, Clone, Default)]
+struct StatefulFileList {
+    state: ListState,
+    items: Vec<StatusEntry>,
+    last_selected: Option<usize>,
+}
+
+impl StatefulFileList {
+    /// Reloads the files from `git status`, keeping the selection in range.
+    fn refresh(&mut self) -> Result<(), GitError> {
+        self.items = git::status()?;
+        if let Some(i) = self.state.selected() {
+            self.state
+                .select(self.items.len().checked_sub(1).map(|last| i.min(last)));
+        }
+        Ok(())
+    }
+
+    /// Stages the selected file, or unstages it if all its changes are staged.
+    fn toggle_staged(&mut self) -> Result<(), GitError> {
+        let Some(entry) = self.state.selected().and_then(|i| self.items.get(i)) else {
+            return Ok(());
+        };
+        if entry.is_staged() {
+            git::unstage(entry)?;
+        } else {
+            git::stage(entry)?;
         }
+        self.refresh()
     }
 
     fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -312,12 +474,15 @@ impl<'a> StatefulFooterList<'a> {
                     i + 1
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
         };
         self.state.select(Some(i));
     }
 
     fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -326,7 +491,7 @@ impl<'a> StatefulFooterList<'a> {
                     i - 1
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
         };
         self.state.select(Some(i));
     }
@@ -338,109 +503,1861 @@ impl<'a> StatefulFooterList<'a> {
         *self.state.offset_mut() = offset;
     }
 }
+
+/// One file's changes, to stage or unstage a hunk or line at a time.
 #[derive(Debug, Clone)]
-struct App<'a> {
-    items: StatefulList<'a>,
-    footer_list: StatefulFooterList<'a>,
+struct DiffView {
+    path: String,
+    /// Showing the changes between `HEAD` and the index rather than those
+    /// between the index and the working tree.
+    staged: bool,
+    diff: FileDiff,
+    /// The hunk of each row and, unless it is the hunk's header, the line.
+    rows: Vec<(usize, Option<usize>)>,
+    state: ListState,
 }
 
-fn main() -> Result<(), Box<dyn Error>> {
-    // setup terminal
-    init_error_hooks()?;
-    let terminal = init_terminal()?;
+impl DiffView {
+    fn open(path: String, staged: bool) -> Result<Self, GitError> {
+        let mut view = DiffView {
+            path,
+            staged,
+            diff: FileDiff::default(),
+            rows: Vec::new(),
+            state: ListState::default(),
+        };
+        view.reload()?;
+        Ok(view)
+    }
 
-    // create app and run it
-    App::new().run(terminal)?;
+    /// Reads the diff again, keeping the cursor in place as far as possible.
+    fn reload(&mut self) -> Result<(), GitError> {
+        self.diff = FileDiff::parse(&git::diff(&self.path, self.staged)?);
+        self.rows = self
+            .diff
+            .hunks
+            .iter()
+            .enumerate()
+            .flat_map(|(i, hunk)| {
+                std::iter::once((i, None)).chain((0..hunk.lines.len()).map(move |l| (i, Some(l))))
+            })
+            .collect();
+        let last = self.rows.len().checked_sub(1);
+        let selected = self.state.selected().unwrap_or(0);
+        self.state.select(last.map(|last| selected.min(last)));
+        Ok(())
+    }
 
-    restore_terminal()?;
+    /// Switches between the staged and unstaged changes.
+    fn switch(&mut self) -> Result<(), GitError> {
+        self.staged = !self.staged;
+        self.state.select(Some(0));
+        self.reload()
+    }
+
+    /// Stages the hunk or line under the cursor, or unstages it when
+    /// showing the staged changes.
+    fn toggle(&mut self) -> Result<(), GitError> {
+        let Some(&(hunk, line)) = self.state.selected().and_then(|i| self.rows.get(i)) else {
+            return Ok(());
+        };
+        let hunk = &self.diff.hunks[hunk];
+        let selected = match line {
+            Some(line) => hunk.select(&[line], self.staged),
+            None => Some(hunk.clone()),
+        };
+        let Some(selected) = selected else {
+            return Ok(());
+        };
+        git::apply_to_index(&self.diff.patch(&selected), self.staged)?;
+        self.reload()
+    }
+
+    fn next(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some((i + 1).min(self.rows.len() - 1)));
+        }
+    }
+
+    fn previous(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+
+    /// Moves to the header of the next hunk, or the previous one.
+    fn jump_hunk(&mut self, forward: bool) {
+        let Some(i) = self.state.selected() else {
+            return;
+        };
+        let is_header = |row: &(usize, Option<usize>)| row.1.is_none();
+        let target = if forward {
+            self.rows
+                .iter()
+                .skip(i + 1)
+                .position(is_header)
+                .map(|p| i + 1 + p)
+        } else {
+            self.rows[..i].iter().rposition(is_header)
+        };
+        if let Some(target) = target {
+            self.state.select(Some(target));
+        }
+    }
+}
+
+/// A read-only view of everything staged, laid out as unified or
+/// side-by-side lines.
+#[derive(Debug, Clone, Default)]
+struct StagedDiffView {
+    files: Vec<FileDiff>,
+    side_by_side: bool,
+    /// The unified lines, or the old side when side by side.
+    left: Vec<Line<'static>>,
+    /// The new side when side by side.
+    right: Vec<Line<'static>>,
+    /// Where each file and each hunk starts.
+    file_starts: Vec<usize>,
+    hunk_starts: Vec<usize>,
+    /// The first line shown.
+    scroll: usize,
+    /// The number of lines that fit, as last drawn.
+    height: usize,
+}
+
+impl StagedDiffView {
+    fn open() -> Result<Self, GitError> {
+        let mut view = StagedDiffView {
+            files: diff::parse_files(&git::staged_patch()?),
+            ..StagedDiffView::default()
+        };
+        view.layout();
+        Ok(view)
+    }
+
+    /// Switches between the unified and side-by-side layouts, staying at
+    /// the same hunk.
+    fn toggle_layout(&mut self) {
+        let hunk = self
+            .hunk_starts
+            .iter()
+            .rposition(|start| *start <= self.scroll);
+        self.side_by_side = !self.side_by_side;
+        self.layout();
+        self.scroll = hunk.map_or(0, |hunk| self.hunk_starts[hunk]);
+    }
+
+    fn layout(&mut self) {
+        self.left.clear();
+        self.right.clear();
+        self.file_starts.clear();
+        self.hunk_starts.clear();
+        for file in &self.files {
+            if !self.left.is_empty() {
+                self.left.push(Line::default());
+                self.right.push(Line::default());
+            }
+            self.file_starts.push(self.left.len());
+            let header = file_header(file);
+            self.left.push(header.clone());
+            self.right.push(header);
+
+            let syntax = highlight::for_path(file.path());
+            for hunk in &file.hunks {
+                self.hunk_starts.push(self.left.len());
+                let header = Line::styled(
+                    format!(
+                        "@@ -{},{} +{},{} @@",
+                        hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+                    ),
+                    HUNK_HEADER_COLOR,
+                );
+                self.left.push(header.clone());
+                self.right.push(header);
+
+                // Changed words of each removed or added line.
+                let text: Vec<String> = hunk
+                    .lines
+                    .iter()
+                    .map(|line| line.replace('\t', "    "))
+                    .collect();
+                let mut changed: HashMap<usize, Vec<Range<usize>>> = HashMap::new();
+                let pairs = hunk.pairs();
+                for &(old, new) in &pairs {
+                    if let (Some(old), Some(new)) = (old, new) {
+                        if old != new && !text[old].starts_with('\\') {
+                            let (removed, added) =
+                                diff::word_diff(&text[old][1..], &text[new][1..]);
+                            changed.insert(old, removed);
+                            changed.insert(new, added);
+                        }
+                    }
+                }
+                let numbers = hunk.line_numbers();
+                let render = |index: usize, gutter: String| {
+                    let mut spans = vec![Span::styled(gutter, PLACEHOLDER_TEXT_COLOR)];
+                    spans.extend(diff_line(
+                        &text[index],
+                        syntax,
+                        changed.get(&index).map_or(&[], Vec::as_slice),
+                    ));
+                    Line::from(spans)
+                };
+
+                if self.side_by_side {
+                    for (old, new) in pairs {
+                        let number = |n: Option<usize>| n.map_or(String::new(), |n| n.to_string());
+                        self.left.push(old.map_or_else(Line::default, |i| {
+                            render(i, format!("{:>4} ", number(numbers[i].0)))
+                        }));
+                        self.right.push(new.map_or_else(Line::default, |i| {
+                            render(i, format!("{:>4} ", number(numbers[i].1)))
+                        }));
+                    }
+                } else {
+                    for (i, (old, new)) in numbers.iter().enumerate() {
+                        let number = |n: &Option<usize>| n.map_or(String::new(), |n| n.to_string());
+                        self.left
+                            .push(render(i, format!("{:>4} {:>4} ", number(old), number(new))));
+                    }
+                }
+            }
+        }
+        self.scroll = self.scroll.min(self.left.len().saturating_sub(1));
+    }
+
+    fn scroll_by(&mut self, lines: isize) {
+        let last = self.left.len().saturating_sub(1);
+        self.scroll = self.scroll.saturating_add_signed(lines).min(last);
+    }
+
+    /// Scrolls to the next or previous hunk, or file.
+    fn jump(&mut self, file: bool, forward: bool) {
+        let starts = if file {
+            &self.file_starts
+        } else {
+            &self.hunk_starts
+        };
+        let target = if forward {
+            starts.iter().find(|start| **start > self.scroll)
+        } else {
+            starts.iter().rev().find(|start| **start < self.scroll)
+        };
+        if let Some(target) = target {
+            self.scroll = *target;
+        }
+    }
+}
+
+/// The path of a file in the staged diff, with what happened to it.
+fn file_header(file: &FileDiff) -> Line<'static> {
+    let notes = [
+        ("new file mode", "new file".to_string()),
+        ("deleted file mode", "deleted".to_string()),
+        ("Binary files", "binary".to_string()),
+        ("old mode", "mode changed".to_string()),
+    ];
+    let mut line = vec![Span::styled(file.path().to_string(), SELECTED_STYLE_FG).bold()];
+    for header in &file.header {
+        if let Some(old_path) = header.strip_prefix("rename from ") {
+            line.push(Span::styled(
+                format!(" (renamed from {old_path})"),
+                PLACEHOLDER_TEXT_COLOR,
+            ));
+        }
+        for (prefix, note) in &notes {
+            if header.starts_with(prefix) {
+                line.push(Span::styled(format!(" ({note})"), PLACEHOLDER_TEXT_COLOR));
+            }
+        }
+    }
+    Line::from(line)
+}
+
+/// Colours one line of a hunk: its code by syntax, on a background that
+/// marks it as added or removed and highlights the `changed` byte ranges
+/// of the code.
+fn diff_line(line: &str, syntax: Option<&Syntax>, changed: &[Range<usize>]) -> Vec<Span<'static>> {
+    let (marker, code) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
+    let (marker_color, line_bg, word_bg) = match marker {
+        "+" => (COMPLETED_TEXT_COLOR, Some(ADDED_BG), ADDED_WORD_BG),
+        "-" => (BREAKING_TEXT_COLOR, Some(REMOVED_BG), REMOVED_WORD_BG),
+        "\\" => return vec![Span::styled(line.to_string(), PLACEHOLDER_TEXT_COLOR)],
+        _ => (TEXT_COLOR, None, NORMAL_ROW_COLOR),
+    };
+    let background = |style: Style| match line_bg {
+        Some(bg) => style.bg(bg),
+        None => style,
+    };
+
+    let mut spans = vec![Span::styled(
+        marker.to_string(),
+        background(Style::new().fg(marker_color)),
+    )];
+    let tokens = match syntax {
+        Some(syntax) => highlight::highlight(code, syntax),
+        None => vec![(0..code.len(), Token::Plain)],
+    };
+    for (range, token) in tokens {
+        let color = match token {
+            Token::Plain => TEXT_COLOR,
+            Token::Keyword => KEYWORD_COLOR,
+            Token::String => FOOTER_TEXT_COLOR,
+            Token::Comment => PLACEHOLDER_TEXT_COLOR,
+            Token::Number => NUMBER_COLOR,
+        };
+        // Split the token where changed words start and end.
+        let mut cuts = vec![range.start, range.end];
+        for word in changed {
+            cuts.extend(
+                [word.start, word.end]
+                    .into_iter()
+                    .filter(|cut| range.contains(cut)),
+            );
+        }
+        cuts.sort_unstable();
+        cuts.dedup();
+        for piece in cuts.windows(2) {
+            let style = Style::new().fg(color);
+            let style = if changed.iter().any(|word| word.contains(&piece[0])) {
+                style.bg(word_bg)
+            } else {
+                background(style)
+            };
+            spans.push(Span::styled(code[piece[0]..piece[1]].to_string(), style));
+        }
+    }
+    spans
+}
+
+/// The value being typed for a footer.
+#[derive(Debug, Clone)]
+struct FooterPrompt {
+    token: String,
+    /// The added footer being edited, or `None` for a new one.
+    index: Option<usize>,
+    /// What the value has to look like.
+    kind: FooterValue,
+    input: TextArea<'static>,
+    /// The `Name <email>` values to complete from, most frequent first.
+    people: Vec<String>,
+    /// The people matching the value typed so far.
+    suggestions: Vec<String>,
+    suggestion_state: ListState,
+}
+
+impl FooterPrompt {
+    fn new(footer: &Footer, index: Option<usize>, kind: FooterValue, people: Vec<String>) -> Self {
+        let value = match footer.separator {
+            Separator::Hash => format!("#{}", footer.value),
+            Separator::Colon => footer.value.clone(),
+        };
+        let mut input = TextArea::from(value.lines().map(str::to_string));
+        input.set_placeholder_text(match kind {
+            _ if footer.is_breaking_change() => "what breaks and how to migrate",
+            FooterValue::Issue => "#123, group/project#123 or a link to the issue",
+            FooterValue::Jira => "PROJ-123, PROJ-124",
+            FooterValue::SmartCommit => "PROJ-123 #time 1h 30m #comment what was done",
+            _ => "value, or #reference for `Token #value`",
+        });
+        input.move_cursor(CursorMove::Bottom);
+        input.move_cursor(CursorMove::End);
+        let mut prompt = FooterPrompt {
+            token: footer.token.clone(),
+            index,
+            kind,
+            input,
+            people,
+            suggestions: Vec::new(),
+            suggestion_state: ListState::default(),
+        };
+        prompt.update_suggestions();
+        prompt
+    }
+
+    /// Matches the people against the value, keeping the highlighted
+    /// suggestion only if it still matches.
+    fn update_suggestions(&mut self) {
+        let value = self.input.lines().join(" ");
+        let selected = self
+            .suggestion_state
+            .selected()
+            .and_then(|i| self.suggestions.get(i).cloned());
+        self.suggestions = complete::complete(value.trim(), &self.people)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        // Nothing is left to complete once a suggestion was taken as is.
+        if self.suggestions.len() == 1 && self.suggestions[0] == value.trim() {
+            self.suggestions.clear();
+        }
+        let selected = selected.and_then(|s| self.suggestions.iter().position(|p| *p == s));
+        self.suggestion_state.select(selected);
+    }
+
+    /// The highlighted suggestion, or the best one with `or_first`.
+    fn suggestion(&self, or_first: bool) -> Option<&str> {
+        match self.suggestion_state.selected() {
+            Some(i) => self.suggestions.get(i),
+            None if or_first => self.suggestions.first(),
+            None => None,
+        }
+        .map(String::as_str)
+    }
+
+    /// Replaces the value with a suggestion.
+    fn fill(&mut self, person: &str) {
+        self.input = TextArea::from([person.to_string()]);
+        self.input.move_cursor(CursorMove::End);
+        self.suggestion_state.select(None);
+        self.update_suggestions();
+    }
+
+    fn next_suggestion(&mut self) {
+        let next = match self.suggestion_state.selected() {
+            Some(i) if i + 1 < self.suggestions.len() => Some(i + 1),
+            Some(i) => Some(i),
+            None => (!self.suggestions.is_empty()).then_some(0),
+        };
+        self.suggestion_state.select(next);
+    }
+
+    fn previous_suggestion(&mut self) {
+        // Moving up from the top suggestion returns to the typed value.
+        let previous = self
+            .suggestion_state
+            .selected()
+            .and_then(|i| i.checked_sub(1));
+        self.suggestion_state.select(previous);
+    }
+
+    /// The footer typed so far, or `None` while the value is empty. A
+    /// leading `#` picks the `Token #value` form, which `BREAKING CHANGE`
+    /// doesn't allow. Tracker references are written the way the tracker
+    /// reads them.
+    fn footer(&self) -> Option<Footer> {
+        let value = self.input.lines().join("\n").trim().to_string();
+        let mut footer = Footer::new(self.token.as_str(), value);
+        if let Some(reference) = footer.value.strip_prefix('#') {
+            if !footer.is_breaking_change() {
+                footer.value = reference.trim_start().to_string();
+                footer.separator = Separator::Hash;
+            }
+        }
+        Some(tracker::format(footer, self.kind)).filter(|footer| !footer.value.is_empty())
+    }
+}
+
+/// The gitmojis on offer, narrowed down by a search.
+#[derive(Debug, Clone)]
+struct GitmojiPicker {
+    search: TextArea<'static>,
+    catalog: Vec<&'static Gitmoji>,
+    matches: Vec<&'static Gitmoji>,
+    state: ListState,
+}
+
+impl GitmojiPicker {
+    fn new(catalog: Vec<&'static Gitmoji>) -> Self {
+        let mut search = TextArea::default();
+        search.set_placeholder_text("search by name or meaning");
+        let mut picker = GitmojiPicker {
+            search,
+            catalog,
+            matches: Vec::new(),
+            state: ListState::default(),
+        };
+        picker.update_matches();
+        picker
+    }
+
+    /// Matches the catalog against the search, best first.
+    fn update_matches(&mut self) {
+        let query = self.search.lines().join(" ");
+        let mut matches: Vec<(usize, &'static Gitmoji)> = self
+            .catalog
+            .iter()
+            .filter_map(|&gitmoji| {
+                let text = format!("{} {}", gitmoji.code, gitmoji.description);
+                complete::fuzzy_score(query.trim(), &text).map(|score| (score, gitmoji))
+            })
+            .collect();
+        matches.sort_by_key(|(score, _)| *score);
+        self.matches = matches.into_iter().map(|(_, gitmoji)| gitmoji).collect();
+        self.state.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn selected(&self) -> Option<&'static Gitmoji> {
+        self.state
+            .selected()
+            .and_then(|i| self.matches.get(i).copied())
+    }
+
+    fn next(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some((i + 1).min(self.matches.len() - 1)));
+        }
+    }
+
+    fn previous(&mut self) {
+        if let Some(i) = self.state.selected() {
+            self.state.select(Some(i.saturating_sub(1)));
+        }
+    }
+}
+
+/// Text inputs shown on the editing screen.
+#[derive(Debug, Clone)]
+struct Editor {
+    scope: TextArea<'static>,
+    description: TextArea<'static>,
+    body: TextArea<'static>,
+}
+
+impl Editor {
+    fn new(convit: &Convit) -> Self {
+        let mut editor = Self {
+            scope: TextArea::default(),
+            description: TextArea::default(),
+            body: TextArea::default(),
+        };
+        editor.load(convit);
+        editor
+    }
+
+    /// Refills the inputs from the saved values, discarding unsaved edits.
+    fn load(&mut self, convit: &Convit) {
+        self.scope = TextArea::from(convit.commit_scope.iter().cloned());
+        self.description = TextArea::from([convit.commit_description.clone()]);
+        self.body = TextArea::from(
+            convit
+                .commit_body
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .map(str::to_string),
+        );
+        self.scope.set_placeholder_text("optional, e.g. parser");
+        self.description
+            .set_placeholder_text("short summary of the change");
+        self.body
+            .set_placeholder_text("optional, longer explanation");
+        for textarea in [&mut self.scope, &mut self.description, &mut self.body] {
+            textarea.move_cursor(CursorMove::Bottom);
+            textarea.move_cursor(CursorMove::End);
+        }
+    }
+
+    fn textarea(&mut self, field: &CurrentlyEditing) -> Option<&mut TextArea<'static>> {
+        match field {
+            CurrentlyEditing::CommitScope => Some(&mut self.scope),
+            CurrentlyEditing::CommitDescription => Some(&mut self.description),
+            CurrentlyEditing::CommitBody => Some(&mut self.body),
+            CurrentlyEditing::CommitType
+            | CurrentlyEditing::CommitFooters
+            | CurrentlyEditing::PickedFooters
+            | CurrentlyEditing::CommitFiles => None,
+        }
+    }
+
+    fn scope(&self) -> Option<String> {
+        Some(self.scope.lines().join(" ").trim().to_string()).filter(|s| !s.is_empty())
+    }
+
+    fn description(&self) -> String {
+        self.description.lines().join(" ").trim().to_string()
+    }
+
+    fn body(&self) -> Option<String> {
+        Some(self.body.lines().join("\n").trim_end().to_string()).filter(|s| !s.is_empty())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct App {
+    items: StatefulList,
+    footer_list: StatefulFooterList,
+    files: StatefulFileList,
+    /// The file opened from the files panel.
+    diff_view: Option<DiffView>,
+    /// Everything staged, opened with `d`.
+    staged_diff: Option<StagedDiffView>,
+    footer_prompt: Option<FooterPrompt>,
+    convit: Convit,
+    editor: Editor,
+    current_screen: CurrentScreen,
+    /// The `COMMIT_EDITMSG` file when running as git's editor.
+    message_file: Option<PathBuf>,
+    commit_options: CommitOptions,
+    /// Shown in a popup until the next key press.
+    error: Option<String>,
+    /// git's summary of the commit created from the TUI.
+    committed: Option<String>,
+    config: Config,
+    /// Rule violations in the composed commit.
+    diagnostics: Vec<Diagnostic>,
+    /// The type and scope proposed from the staged changes.
+    suggestion: Suggestion,
+    /// The ticket found in the branch name.
+    ticket: Option<Ticket>,
+    /// The repository's authors for completing footers, read when first
+    /// needed.
+    authors: Option<Vec<String>>,
+    /// A gitmoji picked instead of the type's.
+    gitmoji: Option<&'static Gitmoji>,
+    gitmoji_picker: Option<GitmojiPicker>,
+    /// The type and scope the body template was last chosen for.
+    template_for: Option<(String, Option<String>)>,
+    /// The body loaded from a template, to tell whether it was edited.
+    template_body: Option<String>,
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let config = match Config::load(default_config()) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("convit: {err}");
+            process::exit(1);
+        }
+    };
+    for warning in &config.warnings {
+        eprintln!("convit: warning: {warning}");
+    }
+
+    let mut args = std::env::args_os().skip(1);
+    let message_file = match args.next() {
+        Some(command) if command == "lint" => {
+            let Some(path) = args.next() else {
+                eprintln!("usage: convit lint <file>");
+                process::exit(2);
+            };
+            if !run_lint(path, &config)? {
+                process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(command) if command == "template" => {
+            let Some(path) = args.next() else {
+                eprintln!("usage: convit template <file> [<source>]");
+                process::exit(2);
+            };
+            return Ok(run_template(path, args.next(), &config)?);
+        }
+        Some(command) if command == "changelog" => {
+            let usage = || -> ! {
+                eprintln!("usage: convit changelog [<from> [<to>]] [--package <name>] [--other]");
+                process::exit(2);
+            };
+            let (mut refs, mut package, mut include_other) = (Vec::new(), None, false);
+            while let Some(arg) = args.next() {
+                let arg = arg.to_string_lossy().into_owned();
+                match arg.as_str() {
+                    "--other" => include_other = true,
+                    "--package" => {
+                        let name = args.next().unwrap_or_else(|| usage());
+                        package = Some(name.to_string_lossy().into_owned());
+                    }
+                    _ if arg.starts_with("--") => usage(),
+                    _ => refs.push(arg),
+                }
+            }
+            let (from, to) = match refs.as_slice() {
+                [] => (None, "HEAD"),
+                [from] => (Some(from.as_str()), "HEAD"),
+                [from, to] => (Some(from.as_str()), to.as_str()),
+                _ => usage(),
+            };
+            if let Err(err) = run_changelog(from, to, package.as_deref(), include_other, &config) {
+                eprintln!("convit: {err}");
+                process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(command) if command == "bump" => {
+            let usage = || -> ! {
+                eprintln!(
+                    "usage: convit bump [--pre <channel>] [--prefix <prefix> | --packages] [--write] [--tag]"
+                );
+                process::exit(2);
+            };
+            let (mut pre, mut prefix, mut tag, mut write) = (None, None, false, false);
+            let mut packages = false;
+            while let Some(arg) = args.next() {
+                let arg = arg.to_string_lossy().into_owned();
+                match arg.as_str() {
+                    "--pre" => pre = Some(args.next().unwrap_or_else(|| usage())),
+                    "--prefix" => prefix = Some(args.next().unwrap_or_else(|| usage())),
+                    "--tag" => tag = true,
+                    "--write" => write = true,
+                    "--packages" => packages = true,
+                    _ => usage(),
+                }
+            }
+            let pre = pre.map(|pre| pre.to_string_lossy().into_owned());
+            let prefix = prefix.map(|prefix| prefix.to_string_lossy().into_owned());
+            let result = if packages {
+                run_bump_packages(pre.as_deref(), tag, write, &config)
+            } else {
+                run_bump(pre.as_deref(), prefix.as_deref(), tag, write, &config)
+            };
+            if let Err(err) = result {
+                eprintln!("convit: {err}");
+                process::exit(1);
+            }
+            return Ok(());
+        }
+        Some(command) if command == "hooks" => {
+            let action = args.next();
+            let flags: Vec<OsString> = args.collect();
+            let changed = match action {
+                Some(action) if action == "install" => {
+                    let existing = if flags.iter().any(|flag| flag == "--force") {
+                        Existing::Overwrite
+                    } else if flags.iter().any(|flag| flag == "--chain") {
+                        Existing::Chain
+                    } else {
+                        Existing::Refuse
+                    };
+                    hooks::install(existing)
+                }
+                Some(action) if action == "uninstall" => hooks::uninstall(),
+                _ => {
+                    eprintln!("usage: convit hooks (install [--chain | --force] | uninstall)");
+                    process::exit(2);
+                }
+            };
+            match changed {
+                Ok(paths) => paths.iter().for_each(|path| println!("{}", path.display())),
+                Err(err) => {
+                    eprintln!("convit: {err}");
+                    process::exit(1);
+                }
+            }
+            return Ok(());
+        }
+        // git passes the message file when convit is its editor.
+        path => path.map(PathBuf::from),
+    };
+
+    let mut app = App::new(config);
+    if let Some(path) = message_file {
+        app.open_message_file(path)?;
+    }
+    if let Some(ticket) = branch::current(&app.config) {
+        app.apply_ticket(ticket);
+    }
+    // Outside a repository there is nothing to suggest from.
+    if let Ok(suggestion) = suggest::from_index(&app.config) {
+        app.apply_suggestion(suggestion);
+    }
+
+    compose(&mut app)?;
+    if let Some(summary) = app.committed {
+        println!("{summary}");
+    }
+
+    Ok(())
+}
+
+/// Runs the TUI until the user commits or quits.
+fn compose(app: &mut App) -> Result<(), Box<dyn Error>> {
+    // setup terminal
+    init_error_hooks()?;
+    let terminal = init_terminal()?;
+
+    // create app and run it
+    app.run(terminal)?;
+
+    restore_terminal()?;
+    Ok(())
+}
+
+/// Prints diagnostics for a commit message file, returning false if any is an error.
+fn run_lint(path: OsString, config: &Config) -> io::Result<bool> {
+    let path = PathBuf::from(path);
+    let text = fs::read_to_string(&path)?;
+
+    let diagnostics = lint::lint(&text, git::comment_char(), config);
+    for diagnostic in &diagnostics {
+        eprintln!("{}:{diagnostic}", path.display());
+    }
+    Ok(rules::commit_status(&diagnostics) == CommitStatus::Ready)
+}
+
+/// Prints the changelog section for the commits in `from..to`, or only
+/// those concerning `package`. A tagged `to` is titled and dated after the
+/// tag, otherwise the section is unreleased.
+fn run_changelog(
+    from: Option<&str>,
+    to: &str,
+    package: Option<&str>,
+    include_other: bool,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let changelog = match package {
+        None => Changelog::from_git(from, to, config)?,
+        Some(name) => {
+            // Commits since the package's last tag, narrowed to the package.
+            let packages = manifest::packages(&git::toplevel()?)?;
+            let package = packages
+                .iter()
+                .find(|package| package.name == name)
+                .ok_or_else(|| format!("no package named `{name}`"))?;
+            let last_tag = version::tags(&format!("{name}@"))?.pop();
+            let from = from.or(last_tag.as_ref().map(|tag| tag.name.as_str()));
+            let range = from.map_or(to.to_string(), |from| format!("{from}..{to}"));
+            let mut changelog = Changelog::new(git::log(&range)?, config);
+            changelog.retain(|entry, commit| version::concerns(&packages, package, entry, commit));
+            changelog
+        }
+    };
+    let section = if git::is_tag(to) {
+        let date = git::commit_date(to)?;
+        changelog.render(to, Some(&date), config, include_other)
+    } else {
+        changelog.render("Unreleased", None, config, include_other)
+    };
+    print!("{section}");
+    Ok(())
+}
+
+/// Prints the next version worked out from the commits since the last
+/// release. With `write`, the manifests are updated and staged and the TUI
+/// opens with a `chore(release)` commit; with `tag`, the release commit or
+/// `HEAD` is tagged, with the changelog section as the tag message.
+fn run_bump(
+    pre: Option<&str>,
+    prefix: Option<&str>,
+    tag: bool,
+    write: bool,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let prefix = match prefix {
+        Some(prefix) => prefix.to_string(),
+        None => version::default_prefix()?,
+    };
+    let Some(recommendation) = version::recommend(&prefix, pre, config)? else {
+        eprintln!("convit: nothing to release");
+        return Ok(());
+    };
+
+    let next = &recommendation.next.name;
+    let previous = recommendation
+        .previous
+        .as_ref()
+        .map_or("no release", |tag| tag.name.as_str());
+    eprintln!("convit: {previous} -> {next} ({} bump)", recommendation.bump);
+
+    if write {
+        let changed = manifest::set_version(&git::toplevel()?, &recommendation.next.version)?;
+        if changed.is_empty() {
+            eprintln!("convit: no manifests to update");
+        } else {
+            for path in &changed {
+                eprintln!("convit: updated {}", path.display());
+            }
+            commit_release(&changed, next.clone(), None, config)?;
+        }
+    }
+
+    if tag {
+        let date = git::commit_date("HEAD")?;
+        let section = recommendation
+            .changelog
+            .render(next, Some(&date), config, false);
+        git::create_tag(next, &section)?;
+    }
+    println!("{next}");
+    Ok(())
+}
+
+/// Prints the next version of each package of a workspace, like
+/// [`run_bump`]. Packages are tagged `name@X.Y.Z`.
+fn run_bump_packages(
+    pre: Option<&str>,
+    tag: bool,
+    write: bool,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    let root = git::toplevel()?;
+    let packages = manifest::packages(&root)?;
+    if packages.is_empty() {
+        return Err("no workspace packages found".into());
+    }
+    let releases = version::recommend_packages(&packages, pre, config)?;
+    if releases.is_empty() {
+        eprintln!("convit: nothing to release");
+        return Ok(());
+    }
+
+    for release in &releases {
+        let recommendation = &release.recommendation;
+        let previous = recommendation
+            .previous
+            .as_ref()
+            .map(|tag| tag.version.to_string())
+            .or(release.package.version.as_ref().map(Version::to_string))
+            .unwrap_or_else(|| "no release".to_string());
+        eprintln!(
+            "convit: {} {previous} -> {} ({} bump)",
+            release.package.name, recommendation.next.version, recommendation.bump
+        );
+    }
+
+    if write {
+        let versions: Vec<(&Package, &Version)> = releases
+            .iter()
+            .map(|release| (&release.package, &release.recommendation.next.version))
+            .collect();
+        let changed = manifest::set_package_versions(&root, &versions)?;
+        if !changed.is_empty() {
+            for path in &changed {
+                eprintln!("convit: updated {}", path.display());
+            }
+            let tags: Vec<&str> = releases
+                .iter()
+                .map(|release| release.recommendation.next.name.as_str())
+                .collect();
+            let (description, body) = match tags.as_slice() {
+                [tag] => (tag.to_string(), None),
+                _ => (
+                    format!("release {} packages", tags.len()),
+                    Some(tags.join("\n")),
+                ),
+            };
+            commit_release(&changed, description, body, config)?;
+        }
+    }
+
+    if tag {
+        let date = git::commit_date("HEAD")?;
+        for release in &releases {
+            let section = release.render(Some(&date), config);
+            git::create_tag(&release.recommendation.next.name, &section)?;
+        }
+    }
+    for release in &releases {
+        println!("{}", release.recommendation.next.name);
+    }
+    Ok(())
+}
+
+/// Stages the updated manifests and opens the TUI with a `chore(release)`
+/// commit. Exits if the user quits without committing.
+fn commit_release(
+    changed: &[PathBuf],
+    description: String,
+    body: Option<String>,
+    config: &Config,
+) -> Result<(), Box<dyn Error>> {
+    git::add(changed)?;
+
+    let mut app = App::new(config.clone());
+    app.load_commit(ConventionalCommit {
+        commit_type: "chore".to_string(),
+        scope: Some("release".to_string()),
+        description,
+        body,
+        ..ConventionalCommit::default()
+    });
+    compose(&mut app)?;
+    match app.committed {
+        Some(summary) => eprintln!("{summary}"),
+        None => {
+            eprintln!("convit: no release commit made, the updated manifests are staged");
+            process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+/// Adds a commented guide to a new, empty commit message for the
+/// `prepare-commit-msg` hook. Messages from `-m`, merges, squashes, amends
+/// and `commit.template` are left alone.
+fn run_template(path: OsString, source: Option<OsString>, config: &Config) -> io::Result<()> {
+    if source.is_some_and(|source| !source.is_empty()) {
+        return Ok(());
+    }
+    let path = PathBuf::from(path);
+    let text = fs::read_to_string(&path)?;
+    let comment_char = git::comment_char();
+    if !git::strip_comments(&text, comment_char).is_empty() {
+        return Ok(());
+    }
+
+    let mut guide = vec![
+        "<type>(<scope>)!: <description>".to_string(),
+        String::new(),
+        format!("Types: {}", config.type_names().join(", ")),
+    ];
+    if !config.scopes.is_empty() {
+        guide.push(format!("Scopes: {}", config.scopes.join(", ")));
+    }
+    guide.push(format!("Footers: {}", config.footer_tokens().join(", ")));
+    let guide = guide
+        .into_iter()
+        .map(|line| format!("{comment_char} {line}").trim_end().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    fs::write(&path, format!("\n{guide}\n{text}"))
+}
+
+fn init_error_hooks() -> color_eyre::Result<()> {
+    let (panic, error) = HookBuilder::default().into_hooks();
+    let panic = panic.into_panic_hook();
+    let error = error.into_eyre_hook();
+    color_eyre::eyre::set_hook(Box::new(move |e| {
+        let _ = restore_terminal();
+        error(e)
+    }))?;
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        panic(info);
+    }));
+    Ok(())
+}
+
+fn init_terminal() -> color_eyre::Result<Terminal<impl Backend>> {
+    enable_raw_mode()?;
+    stdout().execute(EnterAlternateScreen)?;
+    let backend = CrosstermBackend::new(stdout());
+    let terminal = Terminal::new(backend)?;
+    Ok(terminal)
+}
+
+fn restore_terminal() -> color_eyre::Result<()> {
+    disable_raw_mode()?;
+    stdout().execute(LeaveAlternateScreen)?;
+    Ok(())
+}
+
+impl App {
+    fn new(config: Config) -> Self {
+        let mut app = Self {
+            items: StatefulList::with_items(config.types.clone()),
+            footer_list: StatefulFooterList::footer_items(config.footers.clone()),
+            files: StatefulFileList::default(),
+            diff_view: None,
+            staged_diff: None,
+            footer_prompt: None,
+            convit: Convit::default(),
+            editor: Editor::new(&Convit::default()),
+            current_screen: CurrentScreen::Main,
+            message_file: None,
+            commit_options: CommitOptions::default(),
+            error: None,
+            committed: None,
+            config,
+            diagnostics: Vec::new(),
+            suggestion: Suggestion::default(),
+            ticket: None,
+            authors: None,
+            gitmoji: None,
+            gitmoji_picker: None,
+            template_for: None,
+            template_body: None,
+        };
+        app.apply_defaults();
+        // Outside a repository the panel stays empty.
+        let _ = app.files.refresh();
+        app
+    }
+
+    /// Preselects the configured default type, scope and footers. Required
+    /// footers are picked as well so they can't be forgotten.
+    fn apply_defaults(&mut self) {
+        let config = &self.config;
+        if let Some(default_type) = &config.default_type {
+            let selected = self
+                .items
+                .items
+                .iter()
+                .position(|t| &t.name == default_type);
+            self.items.state.select(selected);
+        }
+        self.convit.commit_scope = config.default_scope.clone();
+        for token in config
+            .default_footers
+            .iter()
+            .chain(&config.required_footers)
+        {
+            let footer = Footer::new(token.as_str(), "");
+            if !self
+                .footer_list
+                .picked
+                .iter()
+                .any(|p| p.token == footer.token)
+            {
+                self.footer_list.picked.push(footer);
+            }
+        }
+        self.editor.load(&self.convit);
+        self.update_commit();
+    }
+
+    /// Adds the footer for the branch's ticket unless the message already
+    /// references it, filling in an empty footer of that kind if there is
+    /// one. The ticket's scope is used if there is no scope yet.
+    fn apply_ticket(&mut self, ticket: Ticket) {
+        let reference = ticket.footer.value.trim_start_matches('#');
+        let picked = &mut self.footer_list.picked;
+        let referenced = picked.iter().any(|footer| {
+            footer
+                .value
+                .split(',')
+                .any(|value| value.trim().trim_start_matches('#') == reference)
+        });
+        if !referenced {
+            let empty = picked.iter_mut().find(|footer| {
+                footer.value.trim().is_empty()
+                    && footer.token.eq_ignore_ascii_case(&ticket.footer.token)
+            });
+            match empty {
+                Some(footer) => footer.value = ticket.footer.value.clone(),
+                None => picked.push(ticket.footer.clone()),
+            }
+        }
+        if self.convit.commit_scope.is_none() {
+            self.convit.commit_scope = ticket.scope.clone();
+        }
+        self.ticket = Some(ticket);
+        // A loaded template is expanded again with the ticket.
+        if self.template_body.is_some() {
+            self.template_for = None;
+        }
+        self.editor.load(&self.convit);
+        self.update_commit();
+    }
+
+    /// Preselects the suggested type and scope unless the message being
+    /// edited already has them.
+    fn apply_suggestion(&mut self, suggestion: Suggestion) {
+        if self.convit.commit_type_input.is_empty() {
+            if let Some(commit_type) = &suggestion.commit_type {
+                let selected = self.items.items.iter().position(|t| &t.name == commit_type);
+                self.items.state.select(selected);
+            }
+        }
+        if self.convit.commit_scope.is_none() {
+            self.convit.commit_scope = suggestion.scope.clone();
+        }
+        self.suggestion = suggestion;
+        self.editor.load(&self.convit);
+        self.update_commit();
+    }
+
+    fn start_editing(&mut self) {
+        self.editor.load(&self.convit);
+        self.current_screen = CurrentScreen::Editing;
+        self.convit.currently_editing = Some(CurrentlyEditing::CommitScope);
+    }
+
+    fn next_field(&mut self) {
+        self.convit.currently_editing = match self.convit.currently_editing {
+            Some(CurrentlyEditing::CommitScope) => Some(CurrentlyEditing::CommitDescription),
+            Some(CurrentlyEditing::CommitDescription) => Some(CurrentlyEditing::CommitBody),
+            _ => Some(CurrentlyEditing::CommitScope),
+        };
+    }
+
+    fn save_inputs(&mut self) {
+        self.convit.commit_scope = self.editor.scope();
+        self.convit.commit_description = self.editor.description();
+        self.convit.commit_body = self.editor.body();
+        self.stop_editing();
+    }
+
+    fn stop_editing(&mut self) {
+        self.current_screen = CurrentScreen::Main;
+        self.convit.currently_editing = Some(CurrentlyEditing::CommitType);
+    }
+
+    fn handle_editing_key(&mut self, key: KeyEvent) {
+        let single_line = !matches!(
+            self.convit.currently_editing,
+            Some(CurrentlyEditing::CommitBody)
+        );
+        match key.code {
+            KeyCode::Esc => self.stop_editing(),
+            KeyCode::Tab => self.next_field(),
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.save_inputs()
+            }
+            KeyCode::Enter if single_line => self.save_inputs(),
+            _ => {
+                if let Some(field) = self.convit.currently_editing.clone() {
+                    if let Some(textarea) = self.editor.textarea(&field) {
+                        textarea.input(key);
+                    }
+                }
+            }
+        }
+    }
+
+    fn footers_focused(&self) -> bool {
+        matches!(
+            self.convit.currently_editing,
+            Some(CurrentlyEditing::CommitFooters)
+        )
+    }
+
+    fn picked_focused(&self) -> bool {
+        matches!(
+            self.convit.currently_editing,
+            Some(CurrentlyEditing::PickedFooters)
+        )
+    }
+
+    fn files_focused(&self) -> bool {
+        matches!(
+            self.convit.currently_editing,
+            Some(CurrentlyEditing::CommitFiles)
+        )
+    }
+
+    /// Moves the focus from the types to the footers, the added footers
+    /// and the files.
+    fn toggle_focus(&mut self) {
+        self.convit.currently_editing = if self.footers_focused() {
+            Some(CurrentlyEditing::PickedFooters)
+        } else if self.picked_focused() {
+            Some(CurrentlyEditing::CommitFiles)
+        } else if self.files_focused() {
+            Some(CurrentlyEditing::CommitType)
+        } else {
+            Some(CurrentlyEditing::CommitFooters)
+        };
+    }
+
+    fn next(&mut self) {
+        if self.footers_focused() {
+            self.footer_list.next();
+        } else if self.picked_focused() {
+            self.footer_list.next_picked();
+        } else if self.files_focused() {
+            self.files.next();
+        } else {
+            self.items.next();
+        }
+    }
+
+    fn previous(&mut self) {
+        if self.footers_focused() {
+            self.footer_list.previous();
+        } else if self.picked_focused() {
+            self.footer_list.previous_picked();
+        } else if self.files_focused() {
+            self.files.previous();
+        } else {
+            self.items.previous();
+        }
+    }
+
+    fn unselect(&mut self) {
+        if self.footers_focused() {
+            self.footer_list.unselect();
+        } else if self.picked_focused() {
+            self.footer_list.picked_state.select(None);
+        } else if self.files_focused() {
+            self.files.unselect();
+        } else {
+            self.items.unselect();
+            self.convit.commit_type_input.clear();
+        }
+    }
+
+    /// Stages or unstages the selected file.
+    fn toggle_staged(&mut self) {
+        if !self.files_focused() || self.index_locked() {
+            return;
+        }
+        if let Err(err) = self.files.toggle_staged() {
+            self.error = Some(err.to_string());
+        }
+    }
+
+    /// Whether the index must be left alone because git has already
+    /// written the tree it commits, as it has when convit is its editor.
+    /// Shows why in the error popup.
+    fn index_locked(&mut self) -> bool {
+        if self.message_file.is_some() {
+            self.error = Some(
+                "git is already committing the staged files. Quit, stage and run `git commit` again to change them."
+                    .to_string(),
+            );
+        }
+        self.message_file.is_some()
+    }
+
+    /// Opens the diff of the selected file. Files with unstaged changes
+    /// show those first.
+    fn open_diff(&mut self) {
+        let files = &self.files;
+        let Some(entry) = files.state.selected().and_then(|i| files.items.get(i)) else {
+            return;
+        };
+        if entry.is_untracked() {
+            self.error =
+                Some("Untracked files have no diff yet, stage them with Space.".to_string());
+            return;
+        }
+        match DiffView::open(entry.path.clone(), entry.worktree == ' ') {
+            Ok(view) => {
+                self.diff_view = Some(view);
+                self.current_screen = CurrentScreen::Diff;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn open_staged_diff(&mut self) {
+        match StagedDiffView::open() {
+            Ok(view) if view.files.is_empty() => {
+                self.error = Some("Nothing is staged yet.".to_string());
+            }
+            Ok(view) => {
+                self.staged_diff = Some(view);
+                self.current_screen = CurrentScreen::StagedDiff;
+            }
+            Err(err) => self.error = Some(err.to_string()),
+        }
+    }
+
+    fn handle_staged_diff_key(&mut self, key: KeyEvent) {
+        let Some(view) = &mut self.staged_diff else {
+            return;
+        };
+        let page = view.height.max(1) as isize;
+        let control = key.modifiers.contains(KeyModifiers::CONTROL);
+        use KeyCode::*;
+        match key.code {
+            Esc | Char('q') => {
+                self.staged_diff = None;
+                self.current_screen = CurrentScreen::Main;
+            }
+            Char('d') if control => view.scroll_by(page / 2),
+            Char('u') if control => view.scroll_by(-page / 2),
+            Char('j') | Down => view.scroll_by(1),
+            Char('k') | Up => view.scroll_by(-1),
+            PageDown | Char(' ') => view.scroll_by(page),
+            PageUp => view.scroll_by(-page),
+            Char('n') => view.jump(false, true),
+            Char('p') => view.jump(false, false),
+            Char('N') => view.jump(true, true),
+            Char('P') => view.jump(true, false),
+            Char('s') => view.toggle_layout(),
+            Char('g') => view.scroll = 0,
+            Char('G') => view.scroll_by(isize::MAX),
+            _ => {}
+        }
+    }
+
+    fn handle_diff_key(&mut self, key: KeyEvent) {
+        use KeyCode::*;
+        if key.code == Char(' ') && self.index_locked() {
+            return;
+        }
+        let Some(view) = &mut self.diff_view else {
+            return;
+        };
+        let result = match key.code {
+            Esc | Char('q') => {
+                self.diff_view = None;
+                self.current_screen = CurrentScreen::Main;
+                self.files.refresh()
+            }
+            Tab => view.switch(),
+            Char(' ') => view.toggle().and_then(|()| self.files.refresh()),
+            Char('j') | Down => {
+                view.next();
+                Ok(())
+            }
+            Char('k') | Up => {
+                view.previous();
+                Ok(())
+            }
+            Char('n') => {
+                view.jump_hunk(true);
+                Ok(())
+            }
+            Char('p') => {
+                view.jump_hunk(false);
+                Ok(())
+            }
+            Char('g') => {
+                view.state.select((!view.rows.is_empty()).then_some(0));
+                Ok(())
+            }
+            Char('G') => {
+                view.state.select(view.rows.len().checked_sub(1));
+                Ok(())
+            }
+            _ => Ok(()),
+        };
+        if let Err(err) = result {
+            self.error = Some(err.to_string());
+        }
+    }
+
+    /// Asks for the value of a new footer of the selected kind, or a new
+    /// value for the selected added footer.
+    fn pick_footer(&mut self) {
+        let list = &self.footer_list;
+        let picked = if self.footers_focused() {
+            list.state
+                .selected()
+                .map(|i| (Footer::new(list.items[i].token.as_str(), ""), None))
+        } else if self.picked_focused() {
+            list.picked_state
+                .selected()
+                .and_then(|i| list.picked.get(i).map(|footer| (footer.clone(), Some(i))))
+        } else {
+            None
+        };
+        let Some((footer, index)) = picked else {
+            return;
+        };
+        let kind = self
+            .config
+            .footer(&footer.token)
+            .map_or(FooterValue::Text, |kind| kind.value);
+        let people = match kind {
+            FooterValue::Person => self.authors().to_vec(),
+            _ => Vec::new(),
+        };
+        self.footer_prompt = Some(FooterPrompt::new(&footer, index, kind, people));
+        self.current_screen = CurrentScreen::FooterValue;
+    }
+
+    /// The authors in the history, or none if it can't be read.
+    fn authors(&mut self) -> &[String] {
+        self.authors
+            .get_or_insert_with(|| git::authors().unwrap_or_default())
+    }
+
+    fn handle_footer_key(&mut self, key: KeyEvent) {
+        let Some(prompt) = &mut self.footer_prompt else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Down if !prompt.suggestions.is_empty() => {
+                prompt.next_suggestion();
+                return;
+            }
+            KeyCode::Up if !prompt.suggestions.is_empty() => {
+                prompt.previous_suggestion();
+                return;
+            }
+            KeyCode::Tab if !prompt.suggestions.is_empty() => {
+                if let Some(person) = prompt.suggestion(true).map(str::to_string) {
+                    prompt.fill(&person);
+                }
+                return;
+            }
+            KeyCode::Enter => {
+                if let Some(person) = prompt.suggestion(false).map(str::to_string) {
+                    prompt.fill(&person);
+                }
+                // Saving an empty value removes an added footer.
+                match (prompt.footer(), prompt.index) {
+                    (Some(footer), index) => self.footer_list.save(footer, index),
+                    (None, Some(index)) => {
+                        self.footer_list.picked_state.select(Some(index));
+                        self.footer_list.remove_picked();
+                    }
+                    (None, None) => {}
+                }
+            }
+            _ => {
+                prompt.input.input(key);
+                prompt.update_suggestions();
+                return;
+            }
+        }
+        self.footer_prompt = None;
+        self.current_screen = CurrentScreen::Main;
+    }
+
+    fn remove_footer(&mut self) {
+        if self.picked_focused() {
+            self.footer_list.remove_picked();
+        }
+    }
+
+    fn go_top(&mut self) {
+        if self.footers_focused() {
+            let first = (!self.footer_list.items.is_empty()).then_some(0);
+            self.footer_list.state.select(first);
+        } else if self.picked_focused() {
+            let first = (!self.footer_list.picked.is_empty()).then_some(0);
+            self.footer_list.picked_state.select(first);
+        } else if self.files_focused() {
+            self.files
+                .state
+                .select((!self.files.items.is_empty()).then_some(0));
+        } else {
+            let first = (!self.items.items.is_empty()).then_some(0);
+            self.items.state.select(first);
+        }
+    }
+
+    fn go_bottom(&mut self) {
+        if self.footers_focused() {
+            self.footer_list
+                .state
+                .select(self.footer_list.items.len().checked_sub(1));
+        } else if self.picked_focused() {
+            let last = self.footer_list.picked.len().checked_sub(1);
+            self.footer_list.picked_state.select(last);
+        } else if self.files_focused() {
+            self.files
+                .state
+                .select(self.files.items.len().checked_sub(1));
+        } else {
+            self.items
+                .state
+                .select(self.items.items.len().checked_sub(1));
+        }
+    }
+
+    /// Rebuilds the composed commit from the list selections and the text inputs.
+    /// While editing, the unsaved inputs are used so the preview follows typing.
+    fn update_commit(&mut self) {
+        let commit_type = self
+            .items
+            .state
+            .selected()
+            .map(|i| self.items.items[i].name.clone())
+            // A message's type that isn't in the list is kept as it is.
+            .unwrap_or_else(|| self.convit.commit_type_input.clone());
+        if self.current_screen != CurrentScreen::Editing {
+            self.apply_template(&commit_type);
+        }
+        let (scope, description, body) = if self.current_screen == CurrentScreen::Editing {
+            (
+                self.editor.scope(),
+                self.editor.description(),
+                self.editor.body(),
+            )
+        } else {
+            (
+                self.convit.commit_scope.clone(),
+                self.convit.commit_description.clone(),
+                self.convit.commit_body.clone(),
+            )
+        };
+        let breaking = self.convit.commit_breaking
+            || self
+                .footer_list
+                .picked
+                .iter()
+                .any(Footer::is_breaking_change);
+
+        let gitmoji = self.header_gitmoji(&commit_type);
+        let mut commit = ConventionalCommit {
+            gitmoji,
+            commit_type,
+            scope,
+            breaking,
+            description,
+            body,
+            footers: self.footer_list.picked.clone(),
+            commit_status: CommitStatus::Unready,
+        };
+        // Check the message git gets, as the hook does, so a body paragraph
+        // that reads as footers is caught. Until the header is complete,
+        // the parts are checked instead.
+        let message = commit.to_string();
+        self.diagnostics = if message.parse::<ConventionalCommit>().is_ok() {
+            rules::check_message(&message, &self.config)
+        } else {
+            rules::check(&commit, &self.config)
+        };
+        commit.commit_status = rules::commit_status(&self.diagnostics);
+        self.convit.conventional_commit = commit;
+    }
+
+    /// Loads the template for a newly chosen type or scope into the body,
+    /// unless the body has text of its own.
+    fn apply_template(&mut self, commit_type: &str) {
+        let chosen = (commit_type.to_string(), self.convit.commit_scope.clone());
+        if self.template_for.as_ref() == Some(&chosen) {
+            return;
+        }
+        let body = self.convit.commit_body.as_deref();
+        if body.is_some() && body != self.template_body.as_deref() {
+            return;
+        }
+        let ticket = self
+            .ticket
+            .as_ref()
+            .map(|ticket| ticket.footer.value.clone());
+        let body = self
+            .config
+            .template(commit_type, chosen.1.as_deref())
+            .map(|t| template::expand(&t.body, &Placeholders::current(ticket)))
+            .map(|body| body.trim_end().to_string())
+            .filter(|body| !body.is_empty());
+        self.template_for = Some(chosen);
+        self.template_body = body.clone();
+        self.convit.commit_body = body;
+        self.editor.load(&self.convit);
+    }
+
+    /// The gitmoji to write into the header: the picked one, or with
+    /// gitmoji mode on, the type's.
+    fn header_gitmoji(&self, commit_type: &str) -> Option<HeaderGitmoji> {
+        let mode = self.config.gitmoji.unwrap_or_default();
+        let gitmoji = self.gitmoji.or_else(|| {
+            self.config
+                .gitmoji
+                .and_then(|_| self.config.gitmoji(commit_type))
+        })?;
+        Some(HeaderGitmoji {
+            text: gitmoji.text(mode.format).to_string(),
+            position: mode.position,
+        })
+    }
+
+    /// Opens the picker. A gitmoji that replaces the type has to stand for
+    /// one of the types, so only those are offered then.
+    fn open_gitmoji_picker(&mut self) {
+        let replace =
+            self.config.gitmoji.unwrap_or_default().position == gitmoji::Position::Replace;
+        let catalog = GITMOJIS
+            .iter()
+            .filter(|gitmoji| !replace || self.config.gitmoji_type(gitmoji.emoji).is_some())
+            .collect();
+        self.gitmoji_picker = Some(GitmojiPicker::new(catalog));
+        self.current_screen = CurrentScreen::Gitmoji;
+    }
+
+    fn handle_gitmoji_key(&mut self, key: KeyEvent) {
+        let Some(picker) = &mut self.gitmoji_picker else {
+            return;
+        };
+        match key.code {
+            KeyCode::Esc => {}
+            KeyCode::Enter => match picker.selected() {
+                Some(gitmoji) => self.pick_gitmoji(gitmoji),
+                None => return,
+            },
+            // Back to the type's gitmoji, or none outside gitmoji mode.
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.gitmoji = None;
+            }
+            KeyCode::Down => {
+                picker.next();
+                return;
+            }
+            KeyCode::Up => {
+                picker.previous();
+                return;
+            }
+            _ => {
+                picker.search.input(key);
+                picker.update_matches();
+                return;
+            }
+        }
+        self.gitmoji_picker = None;
+        self.current_screen = CurrentScreen::Main;
+    }
+
+    /// Writes `gitmoji` into the header. In place of the type, it picks
+    /// the type it stands for as well.
+    fn pick_gitmoji(&mut self, gitmoji: &'static Gitmoji) {
+        self.gitmoji = Some(gitmoji);
+        let mode = self.config.gitmoji.unwrap_or_default();
+        if mode.position != gitmoji::Position::Replace {
+            return;
+        }
+        let Some(commit_type) = self.config.gitmoji_type(gitmoji.emoji) else {
+            return;
+        };
+        let commit_type = commit_type.to_string();
+        let selected = self
+            .items
+            .items
+            .iter()
+            .position(|item| item.name == commit_type);
+        self.items.state.select(selected);
+        self.convit.commit_type_input = commit_type;
+    }
 
-    Ok(())
-}
+    /// Fills the selections and inputs from an existing message.
+    fn load_commit(&mut self, commit: ConventionalCommit) {
+        let selected = self
+            .items
+            .items
+            .iter()
+            .position(|item| item.name.eq_ignore_ascii_case(&commit.commit_type));
+        self.items.state.select(selected);
+        self.convit.commit_type_input = commit.commit_type;
+        self.convit.commit_scope = commit.scope;
+        self.convit.commit_description = commit.description;
+        self.convit.commit_body = commit.body;
+        self.convit.commit_breaking = commit.breaking;
+        // A gitmoji other than the one gitmoji mode would write is kept.
+        let type_gitmoji = self
+            .config
+            .gitmoji
+            .and_then(|_| self.config.gitmoji(&self.convit.commit_type_input));
+        self.gitmoji = commit
+            .gitmoji
+            .and_then(|gitmoji| gitmoji::find(&gitmoji.text))
+            .filter(|gitmoji| Some(*gitmoji) != type_gitmoji);
+        self.footer_list.picked = commit.footers;
+        // Existing messages only get a template once the type changes.
+        self.template_for = Some((
+            self.convit.commit_type_input.clone(),
+            self.convit.commit_scope.clone(),
+        ));
+        self.template_body = None;
+        self.editor.load(&self.convit);
+        self.update_commit();
+    }
 
-fn init_error_hooks() -> color_eyre::Result<()> {
-    let (panic, error) = HookBuilder::default().into_hooks();
-    let panic = panic.into_panic_hook();
-    let error = error.into_eyre_hook();
-    color_eyre::eyre::set_hook(Box::new(move |e| {
-        let _ = restore_terminal();
-        error(e)
-    }))?;
-    std::panic::set_hook(Box::new(move |info| {
-        let _ = restore_terminal();
-        panic(info);
-    }));
-    Ok(())
-}
+    /// Opens a commit message file that git handed us as its editor.
+    ///
+    /// Messages that aren't conventional yet, such as merge messages, keep
+    /// their first line as the description and the rest as the body.
+    fn open_message_file(&mut self, path: PathBuf) -> io::Result<()> {
+        let message = git::strip_comments(&fs::read_to_string(&path)?, git::comment_char());
+        let mut commit = message.parse().unwrap_or_else(|_| {
+            let (description, body) = message.split_once('\n').unwrap_or((&message, ""));
+            let body = body.trim();
+            ConventionalCommit {
+                description: description.trim().to_string(),
+                body: Some(body.to_string()).filter(|body| !body.is_empty()),
+                ..ConventionalCommit::default()
+            }
+        });
+        self.config.resolve_gitmoji(&mut commit);
+        self.load_commit(commit);
+        self.message_file = Some(path);
+        Ok(())
+    }
 
-fn init_terminal() -> color_eyre::Result<Terminal<impl Backend>> {
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout());
-    let terminal = Terminal::new(backend)?;
-    Ok(terminal)
-}
+    /// Writes the composed message to the message file and returns true if
+    /// the app should exit. Without a message file, opens the `git commit`
+    /// options instead. Does nothing until the commit is ready.
+    fn confirm(&mut self) -> io::Result<bool> {
+        let commit = &self.convit.conventional_commit;
+        if commit.commit_status != CommitStatus::Ready {
+            return Ok(false);
+        }
+        match &self.message_file {
+            Some(path) => {
+                fs::write(path, format!("{commit}\n"))?;
+                Ok(true)
+            }
+            None => {
+                self.current_screen = CurrentScreen::Confirming;
+                Ok(false)
+            }
+        }
+    }
 
-fn restore_terminal() -> color_eyre::Result<()> {
-    disable_raw_mode()?;
-    stdout().execute(LeaveAlternateScreen)?;
-    Ok(())
-}
+    /// Runs `git commit` and returns true if the app should exit. Failures
+    /// such as rejected hooks are shown in the error popup.
+    fn create_commit(&mut self) -> bool {
+        let message = self.convit.conventional_commit.to_string();
+        self.current_screen = CurrentScreen::Main;
+        match git::commit(&message, self.commit_options) {
+            Ok(summary) => {
+                self.committed = Some(summary);
+                true
+            }
+            Err(err) => {
+                self.error = Some(err.to_string());
+                false
+            }
+        }
+    }
 
-impl<'a> App<'a> {
-    fn new() -> Self {
-        Self {
-            items: StatefulList::with_items(vec![
-                CommitType::Fix,
-                CommitType::Feat,
-                CommitType::Build,
-                CommitType::Chore,
-                CommitType::Ci,
-                CommitType::Docs,
-                CommitType::Style,
-                CommitType::Refactor,
-                CommitType::Perf,
-                CommitType::Test,
-            ]),
-            footer_list: StatefulFooterList::footer_items(vec![
-                CommitFooters::BreakingChange,
-                CommitFooters::SignedOffBy,
-                CommitFooters::AckedBy,
-                CommitFooters::HelpedBy,
-                CommitFooters::ReferenceTo,
-                CommitFooters::SeeAlso,
-                CommitFooters::Fixes,
-                CommitFooters::Cc,
-                CommitFooters::ReviewedBy,
-            ]),
+    fn handle_confirming_key(&mut self, key: KeyEvent) -> bool {
+        let options = &mut self.commit_options;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => self.current_screen = CurrentScreen::Main,
+            KeyCode::Char('a') => options.amend = !options.amend,
+            KeyCode::Char('s') => options.signoff = !options.signoff,
+            KeyCode::Char('n') => options.no_verify = !options.no_verify,
+            KeyCode::Char('e') => options.allow_empty = !options.allow_empty,
+            KeyCode::Enter | KeyCode::Char('c') => return self.create_commit(),
+            _ => {}
         }
+        false
     }
 
-    fn go_top(&mut self) {
-        self.items.state.select(Some(0));
+    /// Leaves the app, emptying the message file so git aborts the commit.
+    fn abort(&mut self) -> io::Result<()> {
+        if let Some(path) = &self.message_file {
+            fs::write(path, "")?;
+        }
+        Ok(())
     }
 
-    fn go_bottom(&mut self) {
-        self.items.state.select(Some(self.items.items.len() - 1));
+    fn toggle_breaking(&mut self) {
+        self.convit.commit_breaking = !self.convit.commit_breaking;
     }
 }
 
-impl App<'_> {
+impl App {
     fn run(&mut self, mut terminal: Terminal<impl Backend>) -> io::Result<()> {
         loop {
             self.draw(&mut terminal)?;
 
             if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    use KeyCode::*;
-                    match key.code {
-                        Char('q') | Esc => return Ok(()),
-                        Char('h') | Left => self.items.unselect(),
-                        Char('j') | Down => self.items.next(),
-                        Char('k') | Up => self.items.previous(),
-                        // Char('l') | Right | Enter => self.change_status(),
-                        Char('g') => self.go_top(),
-                        Char('G') => self.go_bottom(),
-                        _ => {}
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if self.error.take().is_some() {
+                    continue;
+                }
+                match self.current_screen {
+                    CurrentScreen::Editing => {
+                        self.handle_editing_key(key);
+                        self.update_commit();
+                    }
+                    CurrentScreen::Confirming => {
+                        if self.handle_confirming_key(key) {
+                            return Ok(());
+                        }
+                    }
+                    CurrentScreen::Diff => self.handle_diff_key(key),
+                    CurrentScreen::StagedDiff => self.handle_staged_diff_key(key),
+                    CurrentScreen::FooterValue => {
+                        self.handle_footer_key(key);
+                        self.update_commit();
+                    }
+                    CurrentScreen::Gitmoji => {
+                        self.handle_gitmoji_key(key);
+                        self.update_commit();
+                    }
+                    CurrentScreen::Main | CurrentScreen::Exiting => {
+                        use KeyCode::*;
+                        match key.code {
+                            Char('q') | Esc => return self.abort(),
+                            Char('c') if self.confirm()? => return Ok(()),
+                            Char('e') => self.start_editing(),
+                            Char('d') => self.open_staged_diff(),
+                            Char('!') => self.toggle_breaking(),
+                            Char('m') => self.open_gitmoji_picker(),
+                            Tab => self.toggle_focus(),
+                            Char('h') | Left => self.unselect(),
+                            Char('j') | Down => self.next(),
+                            Char('k') | Up => self.previous(),
+                            Char('l') | Right | Enter if self.files_focused() => self.open_diff(),
+                            Char('l') | Right | Enter => self.pick_footer(),
+                            Char('x') | Delete | Backspace => self.remove_footer(),
+                            Char(' ') => self.toggle_staged(),
+                            Char('g') => self.go_top(),
+                            Char('G') => self.go_bottom(),
+                            _ => {}
+                        }
+                        self.update_commit();
                     }
                 }
             }
@@ -453,13 +2370,13 @@ impl App<'_> {
     }
 }
 
-impl Widget for &mut App<'_> {
+impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
         // Create a space for header, todo list and the footer.
         let first_vertical = Layout::vertical([
             Constraint::Length(2),
             Constraint::Min(0),
-            Constraint::Length(2),
+            Constraint::Length(3),
         ]);
         let [header_area, rest_area, footer_area] = first_vertical.areas(area);
 
@@ -468,9 +2385,14 @@ impl Widget for &mut App<'_> {
         let vertical = Layout::vertical([Constraint::Percentage(50), Constraint::Percentage(50)]);
         let [upper_item_list_area, lower_item_list_area] = vertical.areas(rest_area);
 
-        let upper_half = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]);
-    
-        let [commit_types_area, commit_footers_area] = upper_half.areas(upper_item_list_area);
+        let upper_half = Layout::horizontal([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ]);
+
+        let [commit_types_area, commit_footers_area, files_area] =
+            upper_half.areas(upper_item_list_area);
 
         let bottom_half = Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)]);
 
@@ -479,14 +2401,141 @@ impl Widget for &mut App<'_> {
 
         render_title(header_area, buf);
         self.render_todo(commit_types_area, buf);
-        self.render_commit_footers(commit_footers_area, buf);
+        let [footer_list_area, picked_footers_area] =
+            Layout::vertical([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .areas(commit_footers_area);
+        self.render_commit_footers(footer_list_area, buf);
+        self.render_picked_footers(picked_footers_area, buf);
+        self.render_files(files_area, buf);
         self.render_info(commit_type_info, buf);
         self.render_commit_message(commit_message, buf);
-        render_footer(footer_area, buf);
+        render_footer(footer_area, buf, self.current_screen);
+
+        if self.current_screen == CurrentScreen::Editing {
+            self.render_editor(upper_item_list_area, buf);
+        }
+        if self.current_screen == CurrentScreen::Diff {
+            self.render_diff(rest_area, buf);
+        }
+        if self.current_screen == CurrentScreen::StagedDiff {
+            self.render_staged_diff(rest_area, buf);
+        }
+        if self.current_screen == CurrentScreen::FooterValue {
+            let height = match &self.footer_prompt {
+                Some(prompt) if !prompt.people.is_empty() => 50,
+                _ => 30,
+            };
+            self.render_footer_prompt(popup_area(area, 60, height), buf);
+        }
+        if self.current_screen == CurrentScreen::Gitmoji {
+            self.render_gitmoji_picker(popup_area(area, 60, 60), buf);
+        }
+        if self.current_screen == CurrentScreen::Confirming {
+            self.render_commit_options(popup_area(area, 50, 40), buf);
+        }
+        if let Some(error) = &self.error {
+            render_error(error, popup_area(area, 70, 50), buf);
+        }
     }
 }
 
-impl App<'_> {
+impl App {
+    fn render_commit_options(&self, area: Rect, buf: &mut Buffer) {
+        let options = self.commit_options;
+        let option = |enabled: bool, key: &str, flag: &str| {
+            let marker = if enabled { "[x] " } else { "[ ] " };
+            Line::from(vec![
+                Span::styled(marker, COMPLETED_TEXT_COLOR),
+                Span::styled(format!("({key}) "), SELECTED_STYLE_FG),
+                Span::raw(flag.to_string()),
+            ])
+        };
+        let lines = vec![
+            Line::styled(
+                self.convit.conventional_commit.header(),
+                Style::default().bold(),
+            ),
+            Line::default(),
+            option(options.amend, "a", "--amend"),
+            option(options.signoff, "s", "--signoff"),
+            option(options.no_verify, "n", "--no-verify"),
+            option(options.allow_empty, "e", "--allow-empty"),
+            Line::default(),
+            Line::styled("Enter to commit, Esc to go back.", PLACEHOLDER_TEXT_COLOR),
+        ];
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title_alignment(Alignment::Center)
+                    .title("git commit")
+                    .padding(Padding::horizontal(1))
+                    .fg(TEXT_COLOR)
+                    .bg(TODO_HEADER_BG),
+            )
+            .wrap(Wrap { trim: false })
+            .render(area, buf);
+    }
+
+    fn render_editor(&mut self, area: Rect, buf: &mut Buffer) {
+        Clear.render(area, buf);
+        let outer_block = Block::new()
+            .borders(Borders::ALL)
+            .title_alignment(Alignment::Center)
+            .title("Edit Commit")
+            .fg(TEXT_COLOR)
+            .bg(TODO_HEADER_BG);
+        let inner_area = outer_block.inner(area);
+        outer_block.render(area, buf);
+
+        let [scope_area, description_area, body_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(3),
+        ])
+        .areas(inner_area);
+
+        let editing = self.convit.currently_editing.clone();
+        for (field, title, area) in [
+            (CurrentlyEditing::CommitScope, "Scope", scope_area),
+            (
+                CurrentlyEditing::CommitDescription,
+                "Description",
+                description_area,
+            ),
+            (CurrentlyEditing::CommitBody, "Body", body_area),
+        ] {
+            let active = editing.as_ref() == Some(&field);
+            let Some(textarea) = self.editor.textarea(&field) else {
+                continue;
+            };
+
+            let border_color = if active {
+                SELECTED_STYLE_FG
+            } else {
+                TEXT_COLOR
+            };
+            textarea.set_block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .fg(border_color)
+                    .bg(NORMAL_ROW_COLOR),
+            );
+            textarea.set_style(Style::default().fg(TEXT_COLOR));
+            textarea.set_cursor_line_style(Style::default());
+            textarea.set_placeholder_style(Style::default().fg(PLACEHOLDER_TEXT_COLOR));
+            textarea.set_cursor_style(if active {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            });
+            textarea.widget().render(area, buf);
+        }
+    }
+
     fn render_todo(&mut self, area: Rect, buf: &mut Buffer) {
         // We create two blocks, one is for the header (outer) and the other is for list (inner).
         let outer_block = Block::new()
@@ -513,7 +2562,9 @@ impl App<'_> {
             .items
             .iter()
             .enumerate()
-            .map(|(i, commit_type)| commit_type.to_list_item(i))
+            .map(|(i, commit_type)| {
+                to_list_item(&commit_type.name, &commit_type.description, i, None)
+            })
             .collect();
 
         // Create a List from all list items and highlight the currently selected one
@@ -536,15 +2587,64 @@ impl App<'_> {
 
     fn render_info(&self, area: Rect, buf: &mut Buffer) {
         // We get the info depending on the item's state.
-        let info = if let Some(i) = self.items.state.selected() {
-            match self.items.items[i] {
-                CommitType::Fix => "✓ DONE: ".to_string(),
-                CommitType::Feat => "TODO: ".to_string(),
-                _ => "Not impl'd yet".to_string(),
-            }
+        let mut info = vec![if let Some(i) = self.items.state.selected() {
+            let commit_type = &self.items.items[i];
+            Line::from(format!("{}: {}", commit_type.name, commit_type.description))
+        } else if !self.convit.commit_type_input.is_empty() {
+            Line::from(format!(
+                "{}: from the message, not one of the configured types",
+                self.convit.commit_type_input
+            ))
         } else {
-            "Nothing to see here...".to_string()
-        };
+            Line::from("Nothing to see here...")
+        }];
+
+        if let Some(gitmoji) = self
+            .convit
+            .conventional_commit
+            .gitmoji
+            .as_ref()
+            .and_then(|header| gitmoji::find(&header.text))
+        {
+            info.push(Line::from(format!(
+                "gitmoji: {} {} {}",
+                gitmoji.emoji, gitmoji.code, gitmoji.description
+            )));
+        }
+
+        // Why the type and scope were preselected.
+        if !self.suggestion.reasons.is_empty() {
+            info.push(Line::default());
+            info.push(Line::from("Suggested from the staged changes:"));
+        }
+        for reason in &self.suggestion.reasons {
+            info.push(Line::from(format!("  {reason}")));
+        }
+        if let Some(ticket) = &self.ticket {
+            info.push(Line::default());
+            info.push(Line::from(format!("From the branch `{}`:", ticket.branch)));
+            info.push(Line::from(format!("  {}", ticket.footer)));
+            if let Some(scope) = &ticket.scope {
+                info.push(Line::from(format!("  scope `{scope}`")));
+            }
+        }
+
+        // Rule violations in the composed commit, worst first.
+        let mut diagnostics: Vec<&Diagnostic> = self.diagnostics.iter().collect();
+        diagnostics.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.severity));
+        if !diagnostics.is_empty() {
+            info.push(Line::default());
+        }
+        for diagnostic in diagnostics {
+            let color = match diagnostic.severity {
+                Severity::Error => BREAKING_TEXT_COLOR,
+                _ => FOOTER_TEXT_COLOR,
+            };
+            info.push(Line::from(vec![
+                Span::styled(format!("{}: ", diagnostic.severity), color),
+                Span::raw(format!("{} [{}]", diagnostic.message, diagnostic.rule)),
+            ]));
+        }
 
         // We show the list item's info under the list in this paragraph
         let outer_info_block = Block::new()
@@ -576,16 +2676,7 @@ impl App<'_> {
     }
 
     fn render_commit_message(&self, area: Rect, buf: &mut Buffer) {
-        // We get the info depending on the item's state.
-        // let info = if let Some(i) = self.items.state.selected() {
-        //     match self.items.items[i] {
-        //         CommitType::Fix => "✓ DONE: ".to_string(),
-        //         CommitType::Feat => "TODO: ".to_string(),
-        //         _ => "Not impl'd yet".to_string(),
-        //     }
-        // } else {
-        //     "Nothing to see here...".to_string()
-        // };
+        let commit = &self.convit.conventional_commit;
 
         // We show the list item's info under the list in this paragraph
         let outer_info_block = Block::new()
@@ -607,7 +2698,7 @@ impl App<'_> {
         // We can render the header. Inner info will be rendered later
         outer_info_block.render(outer_info_area, buf);
 
-        let info_paragraph = Paragraph::new("Your commit here...")
+        let info_paragraph = Paragraph::new(commit_preview(commit))
             .block(inner_info_block)
             .fg(TEXT_COLOR)
             .wrap(Wrap { trim: false });
@@ -642,7 +2733,10 @@ impl App<'_> {
             .items
             .iter()
             .enumerate()
-            .map(|(i, commit_type)| commit_type.to_list_item(i))
+            .map(|(i, footer)| {
+                let picked = self.footer_list.picked_count(i);
+                to_list_item(&footer.token, &footer.description, i, Some(picked))
+            })
             .collect();
 
         // Create a List from all list items and highlight the currently selected one
@@ -660,7 +2754,376 @@ impl App<'_> {
         // We can now render the item list
         // (look careful we are using StatefulWidget's render.)
         // ratatui::widgets::StatefulWidget::render as stateful_render
-        StatefulWidget::render(items, inner_area, buf, &mut self.items.state);
+        StatefulWidget::render(items, inner_area, buf, &mut self.footer_list.state);
+    }
+}
+
+impl App {
+    fn render_files(&mut self, area: Rect, buf: &mut Buffer) {
+        let outer_block = Block::new()
+            .borders(Borders::ALL)
+            .title_alignment(Alignment::Center)
+            .title("Files")
+            .fg(TEXT_COLOR)
+            .bg(TODO_HEADER_BG);
+        let inner_block = Block::new()
+            .borders(Borders::NONE)
+            .fg(TEXT_COLOR)
+            .bg(NORMAL_ROW_COLOR);
+
+        let outer_area = area;
+        let inner_area = outer_block.inner(outer_area);
+        outer_block.render(outer_area, buf);
+
+        // Status letters as `git status --short` shows them: staged changes
+        // in green, unstaged ones in red.
+        let items: Vec<ListItem> = self
+            .files
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let bg_color = match i % 2 {
+                    0 => NORMAL_ROW_COLOR,
+                    _ => ALT_ROW_COLOR,
+                };
+                let (index_color, worktree_color) = if entry.is_untracked() {
+                    (BREAKING_TEXT_COLOR, BREAKING_TEXT_COLOR)
+                } else {
+                    (COMPLETED_TEXT_COLOR, BREAKING_TEXT_COLOR)
+                };
+                let path = match &entry.old_path {
+                    Some(old_path) => format!("{old_path} -> {}", entry.path),
+                    None => entry.path.clone(),
+                };
+                let line = Line::from(vec![
+                    Span::styled(entry.index.to_string(), index_color),
+                    Span::styled(entry.worktree.to_string(), worktree_color),
+                    Span::styled(format!(" {path}"), TEXT_COLOR),
+                ]);
+                ListItem::new(line).bg(bg_color)
+            })
+            .collect();
+
+        let items = List::new(items)
+            .block(inner_block)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(SELECTED_STYLE_FG),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(items, inner_area, buf, &mut self.files.state);
+    }
+}
+
+impl App {
+    fn render_diff(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(view) = &mut self.diff_view else {
+            return;
+        };
+        let title = if view.staged {
+            format!("Staged changes to {}", view.path)
+        } else {
+            format!("Unstaged changes to {}", view.path)
+        };
+        let outer_block = Block::new()
+            .borders(Borders::ALL)
+            .title_alignment(Alignment::Center)
+            .title(title)
+            .fg(TEXT_COLOR)
+            .bg(TODO_HEADER_BG);
+        let inner_block = Block::new()
+            .borders(Borders::NONE)
+            .fg(TEXT_COLOR)
+            .bg(NORMAL_ROW_COLOR);
+
+        Clear.render(area, buf);
+        let inner_area = outer_block.inner(area);
+        outer_block.render(area, buf);
+
+        if view.rows.is_empty() {
+            let empty = if view.staged {
+                "Nothing staged. Tab shows the unstaged changes."
+            } else {
+                "Nothing left to stage. Tab shows the staged changes."
+            };
+            Paragraph::new(empty)
+                .block(inner_block.padding(Padding::horizontal(1)))
+                .fg(PLACEHOLDER_TEXT_COLOR)
+                .render(inner_area, buf);
+            return;
+        }
+
+        let items: Vec<ListItem> = view
+            .rows
+            .iter()
+            .map(|&(hunk, line)| {
+                let hunk = &view.diff.hunks[hunk];
+                let Some(line) = line else {
+                    let header = format!(
+                        "@@ -{},{} +{},{} @@",
+                        hunk.old_start, hunk.old_lines, hunk.new_start, hunk.new_lines
+                    );
+                    return ListItem::new(Line::styled(header, HUNK_HEADER_COLOR));
+                };
+                let text = hunk.lines[line].replace('\t', "    ");
+                let color = match text.chars().next() {
+                    Some('+') => COMPLETED_TEXT_COLOR,
+                    Some('-') => BREAKING_TEXT_COLOR,
+                    Some('\\') => PLACEHOLDER_TEXT_COLOR,
+                    _ => TEXT_COLOR,
+                };
+                ListItem::new(Line::styled(text, color))
+            })
+            .collect();
+
+        let items = List::new(items)
+            .block(inner_block)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(items, inner_area, buf, &mut view.state);
+    }
+}
+
+impl App {
+    fn render_staged_diff(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(view) = &mut self.staged_diff else {
+            return;
+        };
+        let block = Block::new()
+            .borders(Borders::ALL)
+            .title_alignment(Alignment::Center)
+            .title("Staged Changes")
+            .fg(TEXT_COLOR)
+            .bg(NORMAL_ROW_COLOR);
+        Clear.render(area, buf);
+        let inner_area = block.inner(area);
+        block.render(area, buf);
+        view.height = inner_area.height as usize;
+
+        // Only the visible lines are drawn; both sides scroll together.
+        let visible = |lines: &[Line<'static>]| {
+            let end = (view.scroll + view.height).min(lines.len());
+            lines[view.scroll.min(end)..end].to_vec()
+        };
+        if view.side_by_side {
+            let [left_area, right_area] =
+                Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .spacing(1)
+                    .areas(inner_area);
+            Paragraph::new(visible(&view.left)).render(left_area, buf);
+            Paragraph::new(visible(&view.right)).render(right_area, buf);
+        } else {
+            Paragraph::new(visible(&view.left)).render(inner_area, buf);
+        }
+    }
+}
+
+impl App {
+    fn render_picked_footers(&mut self, area: Rect, buf: &mut Buffer) {
+        let outer_block = Block::new()
+            .borders(Borders::ALL)
+            .title_alignment(Alignment::Center)
+            .title("Added Footers")
+            .fg(TEXT_COLOR)
+            .bg(TODO_HEADER_BG);
+        let inner_block = Block::new()
+            .borders(Borders::NONE)
+            .fg(TEXT_COLOR)
+            .bg(NORMAL_ROW_COLOR);
+        let inner_area = outer_block.inner(area);
+        outer_block.render(area, buf);
+
+        // Footers waiting for a value, like required ones, show a placeholder.
+        let items: Vec<ListItem> = self
+            .footer_list
+            .picked
+            .iter()
+            .enumerate()
+            .map(|(i, footer)| {
+                let bg_color = match i % 2 {
+                    0 => NORMAL_ROW_COLOR,
+                    _ => ALT_ROW_COLOR,
+                };
+                let color = if footer.is_breaking_change() {
+                    BREAKING_TEXT_COLOR
+                } else {
+                    FOOTER_TEXT_COLOR
+                };
+                let value = if footer.value.is_empty() {
+                    Span::styled(": <value>", PLACEHOLDER_TEXT_COLOR)
+                } else {
+                    let text = footer.to_string();
+                    Span::styled(text[footer.token.len()..].replace('\n', " "), TEXT_COLOR)
+                };
+                let mut line = vec![Span::styled(footer.token.clone(), color), value];
+                // Invalid values are flagged where they are listed.
+                if let Some(problem) = rules::footer_value_problem(footer, &self.config) {
+                    line.push(Span::styled(format!("  ✗ {problem}"), BREAKING_TEXT_COLOR));
+                }
+                ListItem::new(Line::from(line)).bg(bg_color)
+            })
+            .collect();
+
+        let items = List::new(items)
+            .block(inner_block)
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(SELECTED_STYLE_FG),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        StatefulWidget::render(items, inner_area, buf, &mut self.footer_list.picked_state);
+    }
+
+    fn render_footer_prompt(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(prompt) = &mut self.footer_prompt else {
+            return;
+        };
+        Clear.render(area, buf);
+        let (area, list_area) = if prompt.people.is_empty() {
+            (area, None)
+        } else {
+            let [input, list] =
+                Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+            (input, Some(list))
+        };
+        // The footer's help text, or what is wrong with the value so far.
+        let help = match prompt
+            .footer()
+            .and_then(|footer| rules::footer_value_problem(&footer, &self.config))
+        {
+            Some(problem) => Line::styled(problem, BREAKING_TEXT_COLOR),
+            None => Line::styled(
+                self.config
+                    .footer(&prompt.token)
+                    .map(|footer| footer.description.clone())
+                    .unwrap_or_default(),
+                PLACEHOLDER_TEXT_COLOR,
+            ),
+        };
+        prompt.input.set_block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title_alignment(Alignment::Center)
+                .title(format!("{} value", prompt.token))
+                .title_bottom(help)
+                .padding(Padding::horizontal(1))
+                .fg(SELECTED_STYLE_FG)
+                .bg(NORMAL_ROW_COLOR),
+        );
+        prompt.input.set_style(Style::default().fg(TEXT_COLOR));
+        prompt.input.set_cursor_line_style(Style::default());
+        prompt
+            .input
+            .set_placeholder_style(Style::default().fg(PLACEHOLDER_TEXT_COLOR));
+        prompt
+            .input
+            .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        prompt.input.widget().render(area, buf);
+
+        let Some(list_area) = list_area else {
+            return;
+        };
+        let items: Vec<ListItem> = prompt
+            .suggestions
+            .iter()
+            .map(|person| ListItem::new(person.clone()).fg(TEXT_COLOR))
+            .collect();
+        let title = if items.is_empty() {
+            "Authors"
+        } else {
+            "Authors (Tab to complete)"
+        };
+        let list = List::new(items)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .fg(SELECTED_STYLE_FG)
+                    .bg(NORMAL_ROW_COLOR),
+            )
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(SELECTED_STYLE_FG),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut prompt.suggestion_state);
+    }
+}
+
+impl App {
+    fn render_gitmoji_picker(&mut self, area: Rect, buf: &mut Buffer) {
+        let Some(picker) = &mut self.gitmoji_picker else {
+            return;
+        };
+        Clear.render(area, buf);
+        let [search_area, list_area] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+        picker.search.set_block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title_alignment(Alignment::Center)
+                .title("Gitmoji")
+                .padding(Padding::horizontal(1))
+                .fg(SELECTED_STYLE_FG)
+                .bg(NORMAL_ROW_COLOR),
+        );
+        picker.search.set_style(Style::default().fg(TEXT_COLOR));
+        picker.search.set_cursor_line_style(Style::default());
+        picker
+            .search
+            .set_placeholder_style(Style::default().fg(PLACEHOLDER_TEXT_COLOR));
+        picker
+            .search
+            .set_cursor_style(Style::default().add_modifier(Modifier::REVERSED));
+        picker.search.widget().render(search_area, buf);
+
+        let items: Vec<ListItem> = picker
+            .matches
+            .iter()
+            .map(|gitmoji| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{}  ", gitmoji.emoji)),
+                    Span::styled(format!("{:<28}", gitmoji.code), FOOTER_TEXT_COLOR),
+                    Span::styled(gitmoji.description, TEXT_COLOR),
+                ]))
+            })
+            .collect();
+        let title = format!("{} of {}", picker.matches.len(), picker.catalog.len());
+        let list = List::new(items)
+            .block(
+                Block::new()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .fg(SELECTED_STYLE_FG)
+                    .bg(NORMAL_ROW_COLOR),
+            )
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .add_modifier(Modifier::REVERSED)
+                    .fg(SELECTED_STYLE_FG),
+            )
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+        StatefulWidget::render(list, list_area, buf, &mut picker.state);
     }
 }
 
@@ -668,32 +3131,122 @@ fn render_title(area: Rect, buf: &mut Buffer) {
     Paragraph::new("Convit").bold().centered().render(area, buf);
 }
 
-fn render_footer(area: Rect, buf: &mut Buffer) {
-    Paragraph::new("\nUse ↓↑ to move, ← to unselect, → to change status, g/G to go top/bottom.")
-        .centered()
+fn render_error(error: &str, area: Rect, buf: &mut Buffer) {
+    Clear.render(area, buf);
+    Paragraph::new(error.replace('\t', "    "))
+        .block(
+            Block::new()
+                .borders(Borders::ALL)
+                .title_alignment(Alignment::Center)
+                .title("Error (press any key)")
+                .padding(Padding::horizontal(1))
+                .fg(BREAKING_TEXT_COLOR)
+                .bg(NORMAL_ROW_COLOR),
+        )
+        .wrap(Wrap { trim: false })
         .render(area, buf);
 }
 
-fn render_commit(area: Rect, buf: &mut Buffer) {
-    Paragraph::new("Commit Message")
-        .bold()
+/// A rect centered in `area`, taking the given percentages of its size.
+fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let [_, area, _] = Layout::vertical([
+        Constraint::Percentage((100 - percent_y) / 2),
+        Constraint::Percentage(percent_y),
+        Constraint::Percentage((100 - percent_y) / 2),
+    ])
+    .areas(area);
+    let [_, area, _] = Layout::horizontal([
+        Constraint::Percentage((100 - percent_x) / 2),
+        Constraint::Percentage(percent_x),
+        Constraint::Percentage((100 - percent_x) / 2),
+    ])
+    .areas(area);
+    area
+}
+
+fn render_footer(area: Rect, buf: &mut Buffer, screen: CurrentScreen) {
+    let hint = match screen {
+        CurrentScreen::Editing => "\nTab to switch fields, Enter or Ctrl-S to save, Esc to cancel.",
+        CurrentScreen::FooterValue => "\nEnter to save, ↓↑ to pick an author and Tab to complete it, an empty value to remove the footer, Esc to cancel.",
+        CurrentScreen::Gitmoji => "\nType to search, ↓↑ to move, Enter to pick, Ctrl-D to go back to the type's gitmoji, Esc to cancel.",
+        CurrentScreen::StagedDiff => "\nUse ↓↑ or PgDn/PgUp to scroll, n/p for the next or previous hunk, N/P for files, s for side by side, Esc to go back.",
+        CurrentScreen::Diff => "\nUse ↓↑ to move, n/p for the next or previous hunk, Space to stage or unstage the hunk or line, Tab for staged changes, Esc to go back.",
+        _ => "\nUse ↓↑ to move, ← to unselect, Tab to switch lists, → to add or edit a footer, x to remove it, Space to stage, e to edit, d for the staged diff, m for a gitmoji, ! for breaking, c to confirm, g/G for top/bottom.",
+    };
+    Paragraph::new(hint)
         .centered()
+        .wrap(Wrap { trim: true })
         .render(area, buf);
+}
+
+/// Styles the composed message, highlighting breaking-change markers and footers.
+/// Missing header parts are shown as dimmed placeholders.
+fn commit_preview(commit: &ConventionalCommit) -> Text<'static> {
+    let placeholder = |text: &str, fallback: &'static str| {
+        if text.is_empty() {
+            Span::styled(fallback, PLACEHOLDER_TEXT_COLOR)
+        } else {
+            Span::raw(text.to_string())
+        }
+    };
+
+    let mut header = match &commit.gitmoji {
+        Some(gitmoji) if gitmoji.position == gitmoji::Position::Replace => {
+            vec![Span::raw(gitmoji.text.clone()).bold()]
+        }
+        Some(gitmoji) => vec![
+            Span::raw(format!("{} ", gitmoji.text)),
+            placeholder(&commit.commit_type, "<type>").bold(),
+        ],
+        None => vec![placeholder(&commit.commit_type, "<type>").bold()],
+    };
+    if let Some(scope) = &commit.scope {
+        header.push(Span::raw(format!("({scope})")));
+    }
+    if commit.breaking {
+        header.push(Span::styled("!", BREAKING_TEXT_COLOR).bold());
+    }
+    header.push(Span::raw(": "));
+    header.push(placeholder(&commit.description, "<description>"));
+
+    let mut lines = vec![Line::from(header)];
+    if let Some(body) = &commit.body {
+        lines.push(Line::default());
+        lines.extend(body.lines().map(|line| Line::raw(line.to_string())));
+    }
+    if !commit.footers.is_empty() {
+        lines.push(Line::default());
+        for footer in &commit.footers {
+            let color = if footer.is_breaking_change() {
+                BREAKING_TEXT_COLOR
+            } else {
+                FOOTER_TEXT_COLOR
+            };
+            let text = footer.to_string();
+            let (token, value) = text.split_at(footer.token.len());
+            lines.push(Line::from(vec![
+                Span::styled(token.to_string(), color).bold(),
+                Span::raw(value.to_string()),
+            ]));
+        }
+    }
 
-    let outer_info_area = area;
+    Text::from(lines)
 }
 
-impl StatefulList<'_> {
-    fn with_items<'a>(items: Vec<CommitType>) -> StatefulList<'a> {
+impl StatefulList {
+    fn with_items(items: Vec<TypeConfig>) -> StatefulList {
         StatefulList {
             state: ListState::default(),
-            commit: ConventionalCommit::new(),
             items,
             last_selected: None,
         }
     }
 
     fn next(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i >= self.items.len() - 1 {
@@ -702,12 +3255,15 @@ impl StatefulList<'_> {
                     i + 1
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
         };
         self.state.select(Some(i));
     }
 
     fn previous(&mut self) {
+        if self.items.is_empty() {
+            return;
+        }
         let i = match self.state.selected() {
             Some(i) => {
                 if i == 0 {
@@ -716,7 +3272,7 @@ impl StatefulList<'_> {
                     i - 1
                 }
             }
-            None => self.last_selected.unwrap_or(0),
+            None => self.last_selected.unwrap_or(0).min(self.items.len() - 1),
         };
         self.state.select(Some(i));
     }
@@ -728,49 +3284,3 @@ impl StatefulList<'_> {
         *self.state.offset_mut() = offset;
     }
 }
-
-// impl StatefulFooterList<'_> {
-//     fn with_footer_items<'a>(items: Vec<CommitFooters>) -> StatefulFooterList<'a> {
-//         StatefulFooterList {
-//             state: ListState::default(),
-//             commit: ConventionalCommit::new(),
-//             items,
-//             last_selected: None,
-//         }
-//     }
-
-//     fn next(&mut self) {
-//         let i = match self.state.selected() {
-//             Some(i) => {
-//                 if i >= self.items.len() - 1 {
-//                     0
-//                 } else {
-//                     i + 1
-//                 }
-//             }
-//             None => self.last_selected.unwrap_or(0),
-//         };
-//         self.state.select(Some(i));
-//     }
-
-//     fn previous(&mut self) {
-//         let i = match self.state.selected() {
-//             Some(i) => {
-//                 if i == 0 {
-//                     self.items.len() - 1
-//                 } else {
-//                     i - 1
-//                 }
-//             }
-//             None => self.last_selected.unwrap_or(0),
-//         };
-//         self.state.select(Some(i));
-//     }
-
-//     fn unselect(&mut self) {
-//         let offset = self.state.offset();
-//         self.last_selected = self.state.selected();
-//         self.state.select(None);
-//         *self.state.offset_mut() = offset;
-//     }
-// }
\ No newline at end of file
@@ -0,0 +1,1106 @@
+use std::{
+    fs, io,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::version::Version;
+
+/// Dependency tables of a `package.json` whose versions matter for a release.
+const NPM_DEPENDENCIES: [&str; 3] = ["dependencies", "peerDependencies", "optionalDependencies"];
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("{}: {source}", path.display())]
+    Json {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("{}: {name} inherits its version from the workspace, give it its own version to release it on its own", path.display())]
+    Inherited { path: PathBuf, name: String },
+}
+
+/// Sets the version in the manifests under `root`: `Cargo.toml` with
+/// `workspace.package` and every workspace member, the requirements of path
+/// dependencies between them, `package.json`, and the workspace's own
+/// packages in `Cargo.lock`. Only the version strings are replaced, so
+/// formatting and comments are kept. Returns the files that changed.
+pub fn set_version(root: &Path, version: &Version) -> Result<Vec<PathBuf>, ManifestError> {
+    let version = version.to_string();
+    let mut changed = Vec::new();
+    let mut crates = Vec::new();
+
+    let mut edits = Vec::new();
+    for path in cargo_manifests(root)? {
+        let Some(text) = read(&path)? else {
+            continue;
+        };
+        let cargo = set_cargo_version(&text, &version);
+        crates.extend(cargo.name.map(|name| (name, version.clone())));
+        edits.push((path, text, cargo.text));
+    }
+    for (path, text, mut updated) in edits {
+        for (name, version) in &crates {
+            updated = set_cargo_requirement(&updated, name, version);
+        }
+        if updated != text {
+            write(&path, &updated)?;
+            changed.push(path);
+        }
+    }
+
+    changed.extend(update_lock(root, &crates)?);
+
+    let package_json = root.join("package.json");
+    if let Some(text) = read(&package_json)? {
+        if let Some(updated) = set_package_json_version(&text, &version) {
+            if updated != text {
+                write(&package_json, &updated)?;
+                changed.push(package_json);
+            }
+        }
+    }
+
+    Ok(changed)
+}
+
+/// What kind of manifest a package has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageKind {
+    Cargo,
+    Npm,
+}
+
+/// A package of a Cargo workspace or an npm workspaces repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    pub name: String,
+    pub kind: PackageKind,
+    /// The package directory relative to the root, empty for the root.
+    pub dir: PathBuf,
+    pub manifest: PathBuf,
+    /// The version in the manifest. `None` when it is inherited from the
+    /// workspace or missing.
+    pub version: Option<Version>,
+    /// The other packages of the repository this one depends on, leaving
+    /// out dev-dependencies.
+    pub dependencies: Vec<String>,
+}
+
+impl Package {
+    /// Whether a path relative to the root is inside the package directory.
+    pub fn contains(&self, path: &str) -> bool {
+        Path::new(path).starts_with(&self.dir)
+    }
+}
+
+/// The packages of the Cargo workspace and the npm workspaces under `root`.
+pub fn packages(root: &Path) -> Result<Vec<Package>, ManifestError> {
+    let mut packages = Vec::new();
+
+    for manifest in cargo_manifests(root)? {
+        let Some(text) = read(&manifest)? else {
+            continue;
+        };
+        let Some(name) = cargo_package_name(&text) else {
+            continue;
+        };
+        packages.push(Package {
+            name,
+            kind: PackageKind::Cargo,
+            dir: relative_dir(root, &manifest),
+            version: cargo_package_version(&text),
+            dependencies: cargo_dependencies(&text),
+            manifest,
+        });
+    }
+
+    let root_json = root.join("package.json");
+    if let Some(text) = read(&root_json)? {
+        let patterns = npm_workspaces(&text).map_err(|source| ManifestError::Json {
+            path: root_json.clone(),
+            source,
+        })?;
+        for pattern in patterns {
+            for dir in expand(root, &pattern) {
+                let manifest = dir.join("package.json");
+                let Some(text) = read(&manifest)? else {
+                    continue;
+                };
+                let json: serde_json::Value =
+                    serde_json::from_str(&text).map_err(|source| ManifestError::Json {
+                        path: manifest.clone(),
+                        source,
+                    })?;
+                let Some(name) = json["name"].as_str() else {
+                    continue;
+                };
+                let dependencies = NPM_DEPENDENCIES
+                    .iter()
+                    .filter_map(|table| json[table].as_object())
+                    .flat_map(|table| table.keys().cloned())
+                    .collect();
+                packages.push(Package {
+                    name: name.to_string(),
+                    kind: PackageKind::Npm,
+                    dir: relative_dir(root, &manifest),
+                    version: json["version"].as_str().and_then(|v| v.parse().ok()),
+                    dependencies,
+                    manifest,
+                });
+            }
+        }
+    }
+
+    // Only dependencies on packages of the repository are kept.
+    let names: Vec<String> = packages.iter().map(|p| p.name.clone()).collect();
+    for package in &mut packages {
+        package
+            .dependencies
+            .retain(|dependency| names.contains(dependency) && *dependency != package.name);
+        package.dependencies.dedup();
+    }
+    Ok(packages)
+}
+
+/// Sets the version of each released package in its manifest and in the
+/// requirements of the packages that depend on it, plus `Cargo.lock`.
+/// Returns the files that changed.
+pub fn set_package_versions(
+    root: &Path,
+    releases: &[(&Package, &Version)],
+) -> Result<Vec<PathBuf>, ManifestError> {
+    let mut edits: Vec<(PathBuf, String)> = Vec::new();
+    // Reads a manifest, or the edited text if it was already changed.
+    let load =
+        |edits: &[(PathBuf, String)], path: &Path| -> Result<Option<String>, ManifestError> {
+            match edits.iter().find(|(edited, _)| edited == path) {
+                Some((_, text)) => Ok(Some(text.clone())),
+                None => read(path),
+            }
+        };
+    let store = |edits: &mut Vec<(PathBuf, String)>, path: &Path, text: String| match edits
+        .iter_mut()
+        .find(|(edited, _)| edited == path)
+    {
+        Some((_, edited)) => *edited = text,
+        None => edits.push((path.to_path_buf(), text)),
+    };
+
+    let cargo_manifests = cargo_manifests(root)?;
+    let mut npm_manifests = vec![root.join("package.json")];
+    for (package, _) in releases {
+        if package.kind == PackageKind::Npm {
+            npm_manifests.push(package.manifest.clone());
+        }
+    }
+    let mut crates = Vec::new();
+
+    for (package, version) in releases {
+        let version = version.to_string();
+        let Some(text) = load(&edits, &package.manifest)? else {
+            continue;
+        };
+        match package.kind {
+            PackageKind::Cargo => {
+                if package.version.is_none() {
+                    return Err(ManifestError::Inherited {
+                        path: package.manifest.clone(),
+                        name: package.name.clone(),
+                    });
+                }
+                store(
+                    &mut edits,
+                    &package.manifest,
+                    set_cargo_package_version(&text, &version),
+                );
+                crates.push((package.name.clone(), version.clone()));
+                for path in &cargo_manifests {
+                    if let Some(text) = load(&edits, path)? {
+                        let updated = set_cargo_requirement(&text, &package.name, &version);
+                        store(&mut edits, path, updated);
+                    }
+                }
+            }
+            PackageKind::Npm => {
+                if let Some(updated) = set_package_json_version(&text, &version) {
+                    store(&mut edits, &package.manifest, updated);
+                }
+                for path in &npm_manifests {
+                    if let Some(text) = load(&edits, path)? {
+                        let updated = set_npm_requirement(&text, &package.name, &version);
+                        store(&mut edits, path, updated);
+                    }
+                }
+            }
+        }
+    }
+
+    let mut changed = Vec::new();
+    for (path, text) in edits {
+        if read(&path)?.as_deref() != Some(text.as_str()) {
+            write(&path, &text)?;
+            changed.push(path);
+        }
+    }
+    changed.extend(update_lock(root, &crates)?);
+    Ok(changed)
+}
+
+/// Whether the 1-based `line` of a `Cargo.toml` is in a dependency table,
+/// dev-dependencies included, or is the header of one.
+pub fn is_dependency_line(text: &str, line: usize) -> bool {
+    let mut table = "";
+    for (number, text) in text.lines().enumerate() {
+        if let Some(name) = table_name(text.trim()) {
+            table = name;
+        }
+        if number + 1 == line {
+            return is_dependency_table(table, true) || dependency_table(table, true).is_some();
+        }
+    }
+    false
+}
+
+/// The root `Cargo.toml` followed by those of the workspace members, or
+/// nothing if there is no root manifest.
+fn cargo_manifests(root: &Path) -> Result<Vec<PathBuf>, ManifestError> {
+    let root_manifest = root.join("Cargo.toml");
+    let Some(text) = read(&root_manifest)? else {
+        return Ok(Vec::new());
+    };
+    let mut manifests = vec![root_manifest];
+    let workspace = workspace_members(&text);
+    let excluded: Vec<PathBuf> = workspace
+        .exclude
+        .iter()
+        .map(|path| root.join(path))
+        .collect();
+    for pattern in &workspace.members {
+        for dir in expand(root, pattern) {
+            let manifest = dir.join("Cargo.toml");
+            if !excluded.contains(&dir) && !manifests.contains(&manifest) && manifest.is_file() {
+                manifests.push(manifest);
+            }
+        }
+    }
+    Ok(manifests)
+}
+
+/// Sets the versions of workspace packages in `Cargo.lock`, returning its
+/// path if it changed.
+fn update_lock(root: &Path, crates: &[(String, String)]) -> Result<Option<PathBuf>, ManifestError> {
+    let lock = root.join("Cargo.lock");
+    let Some(text) = read(&lock)? else {
+        return Ok(None);
+    };
+    let updated = set_lock_versions(&text, crates);
+    if updated == text {
+        return Ok(None);
+    }
+    write(&lock, &updated)?;
+    Ok(Some(lock))
+}
+
+fn read(path: &Path) -> Result<Option<String>, ManifestError> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(ManifestError::Io {
+            path: path.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+fn write(path: &Path, text: &str) -> Result<(), ManifestError> {
+    fs::write(path, text).map_err(|source| ManifestError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// A `Cargo.toml` after its versions were set.
+struct CargoManifest {
+    text: String,
+    /// The package name, if the manifest has a `[package]` whose version
+    /// was set or is inherited from the workspace.
+    name: Option<String>,
+}
+
+/// Sets `package.version` and `workspace.package.version`, leaving versions
+/// inherited with `version.workspace = true` alone.
+fn set_cargo_version(text: &str, version: &str) -> CargoManifest {
+    let mut table = "";
+    let mut name = None;
+    let mut versioned = false;
+    let mut lines = Vec::new();
+
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if let Some(name) = table_name(trimmed) {
+            table = name;
+            lines.push(line.to_string());
+            continue;
+        }
+
+        match (table, key(trimmed)) {
+            ("package", Some("name")) => name = string_value(line).map(|(_, name)| name),
+            ("package", Some("version")) => {
+                versioned = true;
+                if let Some(replaced) = replace_string_value(line, version) {
+                    lines.push(replaced);
+                    continue;
+                }
+            }
+            // `version.workspace = true`
+            ("package", Some(key)) if key.starts_with("version.") => versioned = true,
+            ("workspace.package", Some("version")) => {
+                if let Some(replaced) = replace_string_value(line, version) {
+                    lines.push(replaced);
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        lines.push(line.to_string());
+    }
+
+    CargoManifest {
+        text: lines.concat(),
+        name: name.filter(|_| versioned),
+    }
+}
+
+/// The name of the table a `[table]` or `[[table]]` header line opens.
+fn table_name(line: &str) -> Option<&str> {
+    let name = line.strip_prefix('[')?.trim_start_matches('[');
+    Some(name.split(']').next().unwrap_or_default().trim())
+}
+
+/// The key of a `key = value` line.
+fn key(line: &str) -> Option<&str> {
+    let (key, _) = line.split_once('=')?;
+    let key = key.trim();
+    (!key.is_empty() && !key.starts_with('#')).then_some(key)
+}
+
+/// The byte range and contents of the first quoted string after `=`.
+fn string_value(line: &str) -> Option<(Range<usize>, String)> {
+    let equals = line.find('=')?;
+    let start = equals + line[equals..].find('"')? + 1;
+    let end = start + line[start..].find('"')?;
+    Some((start..end, line[start..end].to_string()))
+}
+
+fn replace_string_value(line: &str, value: &str) -> Option<String> {
+    let (range, _) = string_value(line)?;
+    Some(format!(
+        "{}{value}{}",
+        &line[..range.start],
+        &line[range.end..]
+    ))
+}
+
+/// The `members` and `exclude` patterns of a `[workspace]` table.
+#[derive(Default)]
+struct Workspace {
+    members: Vec<String>,
+    exclude: Vec<String>,
+}
+
+fn workspace_members(text: &str) -> Workspace {
+    let mut workspace = Workspace::default();
+    let mut table = "";
+    // The array being read and the text collected for it so far.
+    let mut array: Option<(bool, String)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some((is_members, collected)) = &mut array {
+            collected.push_str(strip_comment(trimmed));
+            if trimmed.contains(']') {
+                let strings = quoted_strings(collected);
+                if *is_members {
+                    workspace.members = strings;
+                } else {
+                    workspace.exclude = strings;
+                }
+                array = None;
+            }
+            continue;
+        }
+        if let Some(name) = table_name(trimmed) {
+            table = name;
+            continue;
+        }
+        if table != "workspace" {
+            continue;
+        }
+        let is_members = match key(trimmed) {
+            Some("members") => true,
+            Some("exclude") => false,
+            _ => continue,
+        };
+        let value = strip_comment(trimmed.split_once('=').map_or("", |(_, value)| value));
+        if value.contains(']') {
+            let strings = quoted_strings(value);
+            if is_members {
+                workspace.members = strings;
+            } else {
+                workspace.exclude = strings;
+            }
+        } else {
+            array = Some((is_members, value.to_string()));
+        }
+    }
+    workspace
+}
+
+/// The line up to a `#` that isn't inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+fn quoted_strings(text: &str) -> Vec<String> {
+    text.split('"')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// The directories matching a workspace member pattern. `*` and `?` match
+/// within a single path component.
+fn expand(root: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        let mut next = Vec::new();
+        for dir in dirs {
+            if !component.contains(['*', '?']) {
+                next.push(dir.join(component));
+                continue;
+            }
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut matches: Vec<PathBuf> = entries
+                .flatten()
+                .filter(|entry| entry.path().is_dir())
+                .filter(|entry| wildcard_match(component, &entry.file_name().to_string_lossy()))
+                .map(|entry| entry.path())
+                .collect();
+            matches.sort();
+            next.extend(matches);
+        }
+        dirs = next;
+    }
+    dirs
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // matches[j] is whether the pattern so far matches name[..j].
+    let mut matches = vec![false; name.len() + 1];
+    matches[0] = true;
+    for p in pattern {
+        let mut next = vec![false; name.len() + 1];
+        for j in 0..=name.len() {
+            next[j] = match p {
+                '*' => matches[j] || (j > 0 && next[j - 1]),
+                '?' => j > 0 && matches[j - 1],
+                c => j > 0 && matches[j - 1] && name[j - 1] == c,
+            };
+        }
+        matches = next;
+    }
+    matches[name.len()]
+}
+
+/// Sets the versions of the named packages that come from the workspace
+/// rather than a registry or git.
+fn set_lock_versions(text: &str, crates: &[(String, String)]) -> String {
+    // Split into `[[package]]` blocks, each starting at its header.
+    let mut blocks: Vec<Vec<&str>> = vec![Vec::new()];
+    for line in text.split_inclusive('\n') {
+        if line.trim() == "[[package]]" {
+            blocks.push(Vec::new());
+        }
+        blocks
+            .last_mut()
+            .expect("there is always a block")
+            .push(line);
+    }
+
+    let mut out = String::with_capacity(text.len());
+    for block in blocks {
+        let name = block
+            .iter()
+            .find(|line| key(line.trim()) == Some("name"))
+            .and_then(|line| string_value(line))
+            .map(|(_, name)| name);
+        let local = !block.iter().any(|line| key(line.trim()) == Some("source"));
+        let version = crates
+            .iter()
+            .find(|(crate_name, _)| local && name.as_ref() == Some(crate_name))
+            .map(|(_, version)| version);
+        for line in block {
+            match version.and_then(|version| replace_string_value(line, version)) {
+                Some(replaced) if key(line.trim()) == Some("version") => out.push_str(&replaced),
+                _ => out.push_str(line),
+            }
+        }
+    }
+    out
+}
+
+/// Sets the top-level `version` of a `package.json`, or returns `None` if
+/// it has none.
+fn set_package_json_version(text: &str, version: &str) -> Option<String> {
+    let range = json_string(text, &["version"])?;
+    Some(format!(
+        "{}{version}{}",
+        &text[..range.start],
+        &text[range.end..]
+    ))
+}
+
+/// The byte range of the contents of the string at a path of object keys,
+/// such as `["dependencies", "left-pad"]`.
+fn json_string(text: &str, path: &[&str]) -> Option<Range<usize>> {
+    // The key each open object or array was reached by, none for the
+    // outermost one and for array elements.
+    let mut keys: Vec<Option<&str>> = Vec::new();
+    // The last key read in the current object and whether its `:` was seen.
+    let mut last_key: Option<&str> = None;
+    let mut expecting_value = false;
+    let mut chars = text.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' | '[' => {
+                keys.push(last_key.filter(|_| expecting_value));
+                last_key = None;
+                expecting_value = false;
+            }
+            '}' | ']' => {
+                keys.pop();
+                expecting_value = false;
+            }
+            ':' => expecting_value = true,
+            ',' => expecting_value = false,
+            '"' => {
+                let start = i + 1;
+                let mut end = start;
+                while let Some((j, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => {
+                            end = j;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                if !expecting_value {
+                    last_key = Some(&text[start..end]);
+                    continue;
+                }
+                expecting_value = false;
+                let (wanted, parents) = path.split_last()?;
+                let at_path = keys.len() == path.len()
+                    && keys[1..]
+                        .iter()
+                        .zip(parents)
+                        .all(|(key, parent)| *key == Some(parent));
+                if at_path && last_key == Some(wanted) {
+                    return Some(start..end);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The manifest's directory relative to the root.
+fn relative_dir(root: &Path, manifest: &Path) -> PathBuf {
+    manifest
+        .parent()
+        .and_then(|dir| dir.strip_prefix(root).ok())
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+/// The `workspaces` patterns of a root `package.json`, either a list or
+/// under `packages`.
+fn npm_workspaces(text: &str) -> Result<Vec<String>, serde_json::Error> {
+    let json: serde_json::Value = serde_json::from_str(text)?;
+    let workspaces = match &json["workspaces"] {
+        serde_json::Value::Object(workspaces) => workspaces.get("packages"),
+        workspaces => Some(workspaces),
+    };
+    Ok(workspaces
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|pattern| pattern.as_str().map(str::to_string))
+        .collect())
+}
+
+/// The `name` in a `Cargo.toml`'s `[package]` table.
+fn cargo_package_name(text: &str) -> Option<String> {
+    cargo_lines(text)
+        .find(|(table, line)| *table == "package" && key(line) == Some("name"))
+        .and_then(|(_, line)| string_value(line))
+        .map(|(_, name)| name)
+}
+
+/// The `version` in a `Cargo.toml`'s `[package]` table, unless inherited.
+fn cargo_package_version(text: &str) -> Option<Version> {
+    cargo_lines(text)
+        .find(|(table, line)| *table == "package" && key(line) == Some("version"))
+        .and_then(|(_, line)| string_value(line))
+        .and_then(|(_, version)| version.parse().ok())
+}
+
+/// The trimmed lines of a `Cargo.toml` that aren't table headers, with the
+/// table each is in.
+fn cargo_lines(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut table = "";
+    text.lines().filter_map(move |line| {
+        let line = line.trim();
+        match table_name(line) {
+            Some(name) => {
+                table = name;
+                None
+            }
+            None => Some((table, line)),
+        }
+    })
+}
+
+/// Whether a table lists dependencies, such as `dependencies` or
+/// `target.'cfg(unix)'.build-dependencies`. `dev-dependencies` only count
+/// with `dev`.
+fn is_dependency_table(table: &str, dev: bool) -> bool {
+    let table = table.strip_prefix("workspace.").unwrap_or(table);
+    let table = match table.strip_prefix("target.") {
+        // The cfg may contain dots, so only the part after the last one counts.
+        Some(target) => target.rsplit('.').next().unwrap_or_default(),
+        None => table,
+    };
+    table == "dependencies" || table == "build-dependencies" || (dev && table == "dev-dependencies")
+}
+
+/// The dependency named by a `[dependencies.name]` style table, if it is one.
+fn dependency_table(table: &str, dev: bool) -> Option<&str> {
+    let (parent, name) = table.rsplit_once('.')?;
+    is_dependency_table(parent, dev).then(|| name.trim_matches('"'))
+}
+
+/// The names of a `Cargo.toml`'s dependencies, dev-dependencies aside.
+fn cargo_dependencies(text: &str) -> Vec<String> {
+    let mut dependencies = Vec::new();
+    let mut last_table = "";
+    for (table, line) in cargo_lines(text) {
+        if table.starts_with("workspace.") {
+            continue;
+        }
+        if let Some(name) = dependency_table(table, false) {
+            if table != last_table {
+                dependencies.push(name.to_string());
+            }
+        } else if is_dependency_table(table, false) {
+            // `name = ...`, `name.workspace = true` or `"name" = ...`
+            if let Some(key) = key(line) {
+                let name = key.split('.').next().unwrap_or_default();
+                dependencies.push(name.trim_matches('"').to_string());
+            }
+        }
+        last_table = table;
+    }
+    dependencies
+}
+
+/// Sets the `[package]` version of a `Cargo.toml`.
+fn set_cargo_package_version(text: &str, version: &str) -> String {
+    let mut table = "";
+    text.split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if let Some(name) = table_name(trimmed) {
+                table = name;
+            } else if table == "package" && key(trimmed) == Some("version") {
+                if let Some(replaced) = replace_string_value(line, version) {
+                    return replaced;
+                }
+            }
+            line.to_string()
+        })
+        .collect()
+}
+
+/// Updates the version requirement on the dependency `name`, dev-dependencies
+/// included, in inline
+/// tables like `name = { path = "..", version = "0.1" }` and in
+/// `[dependencies.name]` tables, including `[workspace.dependencies]`.
+fn set_cargo_requirement(text: &str, name: &str, version: &str) -> String {
+    let mut table = "";
+    text.split_inclusive('\n')
+        .map(|line| {
+            let trimmed = line.trim();
+            if let Some(header) = table_name(trimmed) {
+                table = header;
+                return line.to_string();
+            }
+            let range =
+                if dependency_table(table, true) == Some(name) && key(trimmed) == Some("version") {
+                    string_value(line).map(|(range, _)| range)
+                } else if is_dependency_table(table, true)
+                    && key(trimmed).map(|key| key.trim_matches('"')) == Some(name)
+                {
+                    inline_version(line)
+                } else {
+                    None
+                };
+            match range
+                .and_then(|range| Some((update_requirement(&line[range.clone()], version)?, range)))
+            {
+                Some((requirement, range)) => {
+                    format!(
+                        "{}{requirement}{}",
+                        &line[..range.start],
+                        &line[range.end..]
+                    )
+                }
+                None => line.to_string(),
+            }
+        })
+        .collect()
+}
+
+/// The range of the `version` string in an inline dependency table.
+fn inline_version(line: &str) -> Option<Range<usize>> {
+    let open = line.find('{')?;
+    let mut offset = open;
+    while let Some(found) = line[offset..].find("version") {
+        let after = offset + found + "version".len();
+        let before = line[..offset + found].trim_end();
+        let rest = line[after..].trim_start();
+        if (before.ends_with('{') || before.ends_with(',')) && rest.starts_with('=') {
+            let start = after + line[after..].find('"')? + 1;
+            let end = start + line[start..].find('"')?;
+            return Some(start..end);
+        }
+        offset = after;
+    }
+    None
+}
+
+/// Updates the dependency `name` in the dependency tables of a
+/// `package.json`, dev-dependencies included.
+fn set_npm_requirement(text: &str, name: &str, version: &str) -> String {
+    let mut text = text.to_string();
+    for table in NPM_DEPENDENCIES.iter().chain(&["devDependencies"]) {
+        let Some(range) = json_string(&text, &[table, name]) else {
+            continue;
+        };
+        if let Some(requirement) = update_requirement(&text[range.clone()], version) {
+            text.replace_range(range, &requirement);
+        }
+    }
+    text
+}
+
+/// Points a plain requirement like `0.1`, `^0.1.0` or `=1.2.3` at a new
+/// version, keeping its operator. Ranges, wildcards and `workspace:`
+/// protocols are left alone.
+fn update_requirement(requirement: &str, version: &str) -> Option<String> {
+    let operator_end = requirement
+        .find(|c: char| !matches!(c, '^' | '~' | '=' | ' '))
+        .unwrap_or(requirement.len());
+    let (operator, current) = requirement.split_at(operator_end);
+    let plain = !current.is_empty()
+        && current
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()));
+    plain.then(|| format!("{operator}{version}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A scratch directory with the given files, removed again on drop.
+    struct Fixture(PathBuf);
+
+    impl Fixture {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let dir = std::env::temp_dir().join(format!("convit-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            for (path, text) in files {
+                let path = dir.join(path);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, text).unwrap();
+            }
+            Fixture(dir)
+        }
+
+        fn read(&self, path: &str) -> String {
+            fs::read_to_string(self.0.join(path)).unwrap()
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn sets_cargo_versions_and_keeps_formatting() {
+        let text = "[package]\nname = \"app\"  # the binary\nversion = \"0.1.0\" # bumped\n\n[dependencies]\nserde = { version = \"1.0\" }\n";
+        let cargo = set_cargo_version(text, "0.2.0");
+        assert_eq!(cargo.name.as_deref(), Some("app"));
+        assert_eq!(
+            cargo.text,
+            "[package]\nname = \"app\"  # the binary\nversion = \"0.2.0\" # bumped\n\n[dependencies]\nserde = { version = \"1.0\" }\n"
+        );
+
+        let text = "[workspace.package]\nversion = \"1.0.0\"\n\n[package]\nname = \"app\"\nversion.workspace = true\n";
+        let cargo = set_cargo_version(text, "1.1.0");
+        assert_eq!(cargo.name.as_deref(), Some("app"));
+        assert!(cargo.text.contains("version = \"1.1.0\"\n"));
+        assert!(cargo.text.contains("version.workspace = true\n"));
+    }
+
+    #[test]
+    fn sets_only_local_lock_versions() {
+        let text = "[[package]]\nname = \"app\"\nversion = \"0.1.0\"\n\n[[package]]\nname = \"serde\"\nversion = \"0.1.0\"\nsource = \"registry+https://github.com/rust-lang/crates.io-index\"\n";
+        let updated = set_lock_versions(text, &[("app".to_string(), "0.2.0".to_string())]);
+        assert_eq!(updated, text.replacen("0.1.0", "0.2.0", 1));
+        let updated = set_lock_versions(text, &[("serde".to_string(), "0.2.0".to_string())]);
+        assert_eq!(updated, text);
+    }
+
+    #[test]
+    fn sets_the_top_level_package_json_version() {
+        let text = r#"{
+  "name": "web",
+  "dependencies": { "version": "1.0.0" },
+  "version": "1.0.0"
+}
+"#;
+        assert_eq!(
+            set_package_json_version(text, "1.1.0").unwrap(),
+            text.replace("\"version\": \"1.0.0\"\n", "\"version\": \"1.1.0\"\n")
+        );
+        assert_eq!(set_package_json_version("{\"name\": \"x\"}", "1.0.0"), None);
+    }
+
+    #[test]
+    fn finds_json_strings_by_path() {
+        let text = r#"{"a": {"b": "x", "c": ["b"]}, "b": "y \" z"}"#;
+        assert_eq!(json_string(text, &["a", "b"]).map(|r| &text[r]), Some("x"));
+        assert_eq!(
+            json_string(text, &["b"]).map(|r| &text[r]),
+            Some("y \\\" z")
+        );
+        assert_eq!(json_string(text, &["c"]), None);
+    }
+
+    #[test]
+    fn set_version_updates_path_dependencies() {
+        let fixture = Fixture::new(
+            "set-version",
+            &[
+                (
+                    "Cargo.toml",
+                    "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.dependencies]\na = { path = \"crates/a\", version = \"0.1.0\" }\n",
+                ),
+                ("crates/a/Cargo.toml", "[package]\nname = \"a\"\nversion = \"0.1.0\"\n"),
+                (
+                    "crates/b/Cargo.toml",
+                    "[package]\nname = \"b\"\nversion = \"0.1.0\"\n\n[dependencies]\na = { path = \"../a\", version = \"0.1.0\" }\nserde = \"0.1.0\"\n",
+                ),
+                (
+                    "Cargo.lock",
+                    "[[package]]\nname = \"a\"\nversion = \"0.1.0\"\n\n[[package]]\nname = \"b\"\nversion = \"0.1.0\"\n",
+                ),
+            ],
+        );
+        let changed = set_version(&fixture.0, &Version::new(0, 2, 0)).unwrap();
+        assert_eq!(changed.len(), 4);
+        assert!(fixture
+            .read("Cargo.toml")
+            .contains("a = { path = \"crates/a\", version = \"0.2.0\" }"));
+        let b = fixture.read("crates/b/Cargo.toml");
+        assert!(b.contains("version = \"0.2.0\"\n\n"));
+        assert!(b.contains("a = { path = \"../a\", version = \"0.2.0\" }"));
+        assert!(b.contains("serde = \"0.1.0\""));
+        assert!(!fixture.read("Cargo.lock").contains("0.1.0"));
+    }
+
+    #[test]
+    fn updates_cargo_requirements() {
+        let text = "[dependencies]\na = { path = \"../a\", version = \"0.1\" }\nb = { version = \"^0.1.0\", path = \"../b\" }\nc = { path = \"../c\", version = \">=0.1, <0.3\" }\n\n[dev-dependencies.a]\npath = \"../a\"\nversion = \"=0.1.0\"\n";
+        let updated = set_cargo_requirement(text, "a", "0.2.0");
+        assert!(updated.contains("a = { path = \"../a\", version = \"0.2.0\" }"));
+        assert!(updated.contains("version = \"=0.2.0\""));
+        let updated = set_cargo_requirement(&updated, "b", "0.2.0");
+        assert!(updated.contains("b = { version = \"^0.2.0\", path = \"../b\" }"));
+        // Ranges are left to the maintainer.
+        assert_eq!(set_cargo_requirement(text, "c", "0.2.0"), text);
+    }
+
+    #[test]
+    fn updates_plain_requirements_only() {
+        assert_eq!(update_requirement("0.1", "0.2.0").as_deref(), Some("0.2.0"));
+        assert_eq!(
+            update_requirement("~1.2.3", "1.3.0").as_deref(),
+            Some("~1.3.0")
+        );
+        assert_eq!(update_requirement("*", "1.0.0"), None);
+        assert_eq!(update_requirement("workspace:*", "1.0.0"), None);
+        assert_eq!(update_requirement("1.x", "2.0.0"), None);
+    }
+
+    #[test]
+    fn updates_npm_requirements() {
+        let text = r#"{"dependencies": {"ui": "^1.0.0"}, "devDependencies": {"ui": "1.0.0"}, "peerDependencies": {"ui": ">=1"}}"#;
+        assert_eq!(
+            set_npm_requirement(text, "ui", "1.1.0"),
+            r#"{"dependencies": {"ui": "^1.1.0"}, "devDependencies": {"ui": "1.1.0"}, "peerDependencies": {"ui": ">=1"}}"#
+        );
+    }
+
+    #[test]
+    fn lists_cargo_dependencies_without_dev_dependencies() {
+        let text = "[dependencies]\nserde = \"1\"\nlocal.workspace = true\n\"quoted\" = \"1\"\n\n[target.'cfg(unix)'.build-dependencies]\nlibc = \"0.2\"\n\n[dependencies.regex]\nversion = \"1\"\n\n[dev-dependencies]\ntempfile = \"3\"\n";
+        assert_eq!(
+            cargo_dependencies(text),
+            ["serde", "local", "quoted", "libc", "regex"]
+        );
+        assert!(is_dependency_line(text, 2));
+        assert!(is_dependency_line(text, 13));
+        assert!(!is_dependency_line("[package]\nname = \"x\"\n", 2));
+    }
+
+    #[test]
+    fn finds_workspace_packages() {
+        let fixture = Fixture::new(
+            "packages",
+            &[
+                (
+                    "Cargo.toml",
+                    "[workspace]\nmembers = [\n  \"crates/*\", # all of them\n]\nexclude = [\"crates/old\"]\n",
+                ),
+                (
+                    "crates/core/Cargo.toml",
+                    "[package]\nname = \"core\"\nversion = \"0.3.0\"\n",
+                ),
+                (
+                    "crates/cli/Cargo.toml",
+                    "[package]\nname = \"cli\"\nversion.workspace = true\n\n[dependencies]\ncore = { path = \"../core\" }\nserde = \"1\"\n",
+                ),
+                ("crates/old/Cargo.toml", "[package]\nname = \"old\"\n"),
+                ("package.json", r#"{"workspaces": {"packages": ["web"]}}"#),
+                (
+                    "web/package.json",
+                    r#"{"name": "web", "version": "1.0.0", "dependencies": {"ui": "1"}}"#,
+                ),
+            ],
+        );
+        let packages = packages(&fixture.0).unwrap();
+        let summary: Vec<_> = packages
+            .iter()
+            .map(|p| {
+                (
+                    p.name.as_str(),
+                    p.dir.to_string_lossy().into_owned(),
+                    p.version.as_ref().map(Version::to_string),
+                    p.dependencies.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "cli",
+                    "crates/cli".to_string(),
+                    None,
+                    vec!["core".to_string()]
+                ),
+                (
+                    "core",
+                    "crates/core".to_string(),
+                    Some("0.3.0".to_string()),
+                    vec![]
+                ),
+                ("web", "web".to_string(), Some("1.0.0".to_string()), vec![]),
+            ]
+        );
+        assert!(packages[0].contains("crates/cli/src/main.rs"));
+        assert!(!packages[0].contains("crates/client/lib.rs"));
+    }
+
+    #[test]
+    fn set_package_versions_updates_dependents() {
+        let fixture = Fixture::new(
+            "package-versions",
+            &[
+                ("Cargo.toml", "[workspace]\nmembers = [\"a\", \"b\"]\n"),
+                ("a/Cargo.toml", "[package]\nname = \"a\"\nversion = \"1.0.0\"\n"),
+                (
+                    "b/Cargo.toml",
+                    "[package]\nname = \"b\"\nversion = \"2.0.0\"\n\n[dependencies]\na = { path = \"../a\", version = \"1.0.0\" }\n",
+                ),
+            ],
+        );
+        let packages = packages(&fixture.0).unwrap();
+        let version = Version::new(1, 1, 0);
+        let changed = set_package_versions(&fixture.0, &[(&packages[0], &version)]).unwrap();
+        assert_eq!(changed.len(), 2);
+        assert!(fixture.read("a/Cargo.toml").contains("version = \"1.1.0\""));
+        let b = fixture.read("b/Cargo.toml");
+        assert!(b.contains("version = \"2.0.0\""));
+        assert!(b.contains("a = { path = \"../a\", version = \"1.1.0\" }"));
+    }
+
+    #[test]
+    fn refuses_inherited_package_versions() {
+        let fixture = Fixture::new(
+            "inherited",
+            &[
+                (
+                    "Cargo.toml",
+                    "[workspace]\nmembers = [\"a\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+                ),
+                (
+                    "a/Cargo.toml",
+                    "[package]\nname = \"a\"\nversion.workspace = true\n",
+                ),
+            ],
+        );
+        let packages = packages(&fixture.0).unwrap();
+        let result = set_package_versions(&fixture.0, &[(&packages[0], &Version::new(1, 1, 0))]);
+        assert!(matches!(result, Err(ManifestError::Inherited { .. })));
+    }
+}
@@ -0,0 +1,759 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commit::{self, CommitStatus, ConventionalCommit, Footer, ParseError, Separator},
+    config::{Config, FooterValue},
+    gitmoji::Position,
+    tracker,
+};
+
+/// How a rule violation is reported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Warn,
+    #[default]
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Off => "off",
+            Severity::Warn => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// A rule with a length limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Limit {
+    pub severity: Severity,
+    pub max: usize,
+}
+
+/// The commit policy, set under `rules` in config files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    pub header_max_length: Limit,
+    /// The type must be one of the configured types.
+    pub type_enum: Severity,
+    pub type_lowercase: Severity,
+    /// The scope must be one of the configured scopes, if any are configured.
+    pub scope_enum: Severity,
+    pub description_empty: Severity,
+    pub description_full_stop: Severity,
+    pub body_max_line_length: Limit,
+    pub body_leading_blank: Severity,
+    /// Footer tokens must be one of the configured footers.
+    pub footer_enum: Severity,
+    /// Footers required globally or by the commit's type must be present.
+    pub footer_required: Severity,
+    /// Footer values must have the form their footer expects.
+    pub footer_value: Severity,
+    /// Breaking changes must be explained in a `BREAKING CHANGE` footer.
+    pub breaking_change_description: Severity,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            header_max_length: Limit {
+                severity: Severity::Error,
+                max: 100,
+            },
+            type_enum: Severity::Error,
+            type_lowercase: Severity::Error,
+            scope_enum: Severity::Error,
+            description_empty: Severity::Error,
+            description_full_stop: Severity::Error,
+            body_max_line_length: Limit {
+                severity: Severity::Warn,
+                max: 100,
+            },
+            body_leading_blank: Severity::Warn,
+            footer_enum: Severity::Error,
+            footer_required: Severity::Error,
+            footer_value: Severity::Error,
+            breaking_change_description: Severity::Warn,
+        }
+    }
+}
+
+/// A rule violation. Positions are 1-based lines and columns of the message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {} [{}]",
+            self.line, self.column, self.severity, self.message, self.rule
+        )
+    }
+}
+
+/// Ready unless a diagnostic is an error.
+pub fn commit_status(diagnostics: &[Diagnostic]) -> CommitStatus {
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        CommitStatus::Unready
+    } else {
+        CommitStatus::Ready
+    }
+}
+
+/// Where the parts of a message sit, so diagnostics can point at them.
+struct Layout<'a> {
+    /// Body lines with their line numbers.
+    body: Vec<(usize, &'a str)>,
+    /// The line number of each footer, in order.
+    footers: Vec<usize>,
+    last_line: usize,
+    /// Set when the line after the header is not blank.
+    missing_blank: Option<usize>,
+}
+
+/// Checks a commit as it would be written by its `Display` implementation.
+pub fn check(commit: &ConventionalCommit, config: &Config) -> Vec<Diagnostic> {
+    let body: Vec<(usize, &str)> = commit
+        .body
+        .iter()
+        .flat_map(|body| body.lines())
+        .enumerate()
+        .map(|(i, line)| (i + 3, line))
+        .collect();
+
+    let mut line = body.last().map_or(3, |(number, _)| number + 2);
+    let mut footers = Vec::new();
+    for footer in &commit.footers {
+        footers.push(line);
+        line += footer.value.lines().count().max(1);
+    }
+
+    let layout = Layout {
+        body,
+        footers,
+        last_line: line.saturating_sub(1).max(1),
+        missing_blank: None,
+    };
+    check_layout(commit, &layout, config)
+}
+
+/// Parses and checks a commit message whose header is on the first line.
+/// Comment lines must already be removed.
+pub fn check_message(message: &str, config: &Config) -> Vec<Diagnostic> {
+    let mut commit: ConventionalCommit = match message.parse() {
+        Ok(commit) => commit,
+        Err(err) => return parse_error(err, &config.rules),
+    };
+    config.resolve_gitmoji(&mut commit);
+
+    let lines: Vec<&str> = message.lines().collect();
+
+    // Footers are parsed in order from the last paragraph, so each one is
+    // found there after the previous.
+    let rest: Vec<&str> = message
+        .trim_end()
+        .lines()
+        .skip(1)
+        .map(str::trim_end)
+        .collect();
+    let mut footers = Vec::new();
+    let mut next = commit::footer_block_start(&rest) + 1;
+    for footer in &commit.footers {
+        let found = lines.iter().enumerate().skip(next).find(|(_, line)| {
+            Footer::parse_line(line).is_some_and(|parsed| parsed.token == footer.token)
+        });
+        if let Some((i, _)) = found {
+            footers.push(i + 1);
+            next = i + 1;
+        }
+    }
+
+    let body_end = footers.first().map_or(lines.len(), |line| line - 1);
+    let body = lines[1.min(lines.len())..body_end]
+        .iter()
+        .enumerate()
+        .map(|(i, line)| (i + 2, *line))
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect();
+
+    let missing_blank = lines
+        .get(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|_| 2);
+
+    let layout = Layout {
+        body,
+        footers,
+        last_line: lines.len().max(1),
+        missing_blank,
+    };
+    check_layout(&commit, &layout, config)
+}
+
+fn parse_error(err: ParseError, rules: &Rules) -> Vec<Diagnostic> {
+    let (rule, severity) = match err {
+        ParseError::MissingDescription { .. } => ("description_empty", rules.description_empty),
+        _ => ("header_format", Severity::Error),
+    };
+    if severity == Severity::Off {
+        return Vec::new();
+    }
+    vec![Diagnostic {
+        rule,
+        severity,
+        line: 1,
+        column: err.column(),
+        message: err.to_string(),
+    }]
+}
+
+fn check_layout(commit: &ConventionalCommit, layout: &Layout, config: &Config) -> Vec<Diagnostic> {
+    let rules = &config.rules;
+    let mut diagnostics = Vec::new();
+    let mut report =
+        |rule: &'static str, severity: Severity, line: usize, column: usize, message: String| {
+            if severity != Severity::Off {
+                diagnostics.push(Diagnostic {
+                    rule,
+                    severity,
+                    line,
+                    column,
+                    message,
+                });
+            }
+        };
+
+    let header = commit.header();
+    let header_length = header.chars().count();
+    let limit = rules.header_max_length;
+    if header_length > limit.max {
+        report(
+            "header_max_length",
+            limit.severity,
+            1,
+            limit.max + 1,
+            format!(
+                "header is {header_length} characters long, the limit is {}",
+                limit.max
+            ),
+        );
+    }
+
+    let commit_type = &commit.commit_type;
+    // A gitmoji in place of the type has to stand for it.
+    let mismatch = commit
+        .gitmoji
+        .as_ref()
+        .filter(|gitmoji| gitmoji.position == Position::Replace)
+        .map(|gitmoji| (gitmoji, config.gitmoji_type(&gitmoji.text)))
+        .filter(|(_, stands_for)| *stands_for != Some(commit_type.as_str()))
+        .filter(|(_, stands_for)| stands_for.is_none() || !commit_type.is_empty());
+    if let Some((gitmoji, stands_for)) = mismatch {
+        let message = match stands_for {
+            Some(other) => format!(
+                "gitmoji `{}` stands for `{other}`, not `{commit_type}`",
+                gitmoji.text
+            ),
+            None => format!("gitmoji `{}` doesn't stand for any type", gitmoji.text),
+        };
+        report("header_format", Severity::Error, 1, 1, message);
+    } else if commit_type.is_empty() {
+        report(
+            "header_format",
+            Severity::Error,
+            1,
+            1,
+            "missing commit type".to_string(),
+        );
+    } else if !config.types.iter().any(|t| &t.name == commit_type) {
+        report(
+            "type_enum",
+            rules.type_enum,
+            1,
+            1,
+            format!(
+                "unknown commit type `{commit_type}`, expected one of: {}",
+                config.type_names().join(", ")
+            ),
+        );
+    }
+    if commit_type.chars().any(char::is_uppercase) {
+        report(
+            "type_lowercase",
+            rules.type_lowercase,
+            1,
+            1,
+            format!("commit type `{commit_type}` must be lowercase"),
+        );
+    }
+
+    let scope_column = commit_type.chars().count() + 2;
+    if let Some(scope) = &commit.scope {
+        if !config.scopes.is_empty() && !config.scopes.contains(scope) {
+            report(
+                "scope_enum",
+                rules.scope_enum,
+                1,
+                scope_column,
+                format!(
+                    "scope `{scope}` is not allowed, expected one of: {}",
+                    config.scopes.join(", ")
+                ),
+            );
+        }
+    }
+
+    let description_column = header_length - commit.description.chars().count() + 1;
+    if commit.description.trim().is_empty() {
+        report(
+            "description_empty",
+            rules.description_empty,
+            1,
+            description_column,
+            "missing description".to_string(),
+        );
+    } else if commit.description.ends_with('.') {
+        report(
+            "description_full_stop",
+            rules.description_full_stop,
+            1,
+            header_length,
+            "description must not end with a period".to_string(),
+        );
+    }
+
+    if let Some(line) = layout.missing_blank {
+        report(
+            "body_leading_blank",
+            rules.body_leading_blank,
+            line,
+            1,
+            "the body must be separated from the header by a blank line".to_string(),
+        );
+    }
+
+    let limit = rules.body_max_line_length;
+    for (line, text) in &layout.body {
+        let length = text.chars().count();
+        if length > limit.max {
+            report(
+                "body_max_line_length",
+                limit.severity,
+                *line,
+                limit.max + 1,
+                format!(
+                    "body line is {length} characters long, the limit is {}",
+                    limit.max
+                ),
+            );
+        }
+    }
+
+    for (footer, line) in commit.footers.iter().zip(&layout.footers) {
+        if !footer.is_breaking_change() && config.footer(&footer.token).is_none() {
+            report(
+                "footer_enum",
+                rules.footer_enum,
+                *line,
+                1,
+                format!(
+                    "unknown footer `{}`, expected one of: {}",
+                    footer.token,
+                    config.footer_tokens().join(", ")
+                ),
+            );
+        }
+        if let Some(problem) = footer_value_problem(footer, config) {
+            // The value starts after `: ` or ` #`.
+            let column = footer.token.chars().count() + 3;
+            report("footer_value", rules.footer_value, *line, column, problem);
+        }
+    }
+
+    let type_required = config
+        .types
+        .iter()
+        .find(|t| &t.name == commit_type)
+        .map(|t| t.required_footers.as_slice())
+        .unwrap_or_default();
+    for required in config.required_footers.iter().chain(type_required) {
+        if !commit
+            .footers
+            .iter()
+            .any(|f| f.token.eq_ignore_ascii_case(required))
+        {
+            report(
+                "footer_required",
+                rules.footer_required,
+                layout.last_line,
+                1,
+                format!("missing required footer `{required}`"),
+            );
+        }
+    }
+
+    let explained = commit
+        .footers
+        .iter()
+        .any(|f| f.is_breaking_change() && !f.value.trim().is_empty());
+    if commit.is_breaking() && !explained {
+        let column = if commit.breaking {
+            description_column - 3
+        } else {
+            1
+        };
+        report(
+            "breaking_change_description",
+            rules.breaking_change_description,
+            1,
+            column,
+            "breaking changes must be described in a `BREAKING CHANGE:` footer".to_string(),
+        );
+    }
+
+    diagnostics.sort_by_key(|diagnostic| (diagnostic.line, diagnostic.column));
+    diagnostics
+}
+
+/// What is wrong with a footer's value for the configured footer of its
+/// kind, if anything. Unknown footers are left to `footer_enum`.
+pub fn footer_value_problem(footer: &Footer, config: &Config) -> Option<String> {
+    let kind = config.footers.iter().find(|kind| kind.matches(footer))?;
+    let value = footer.value.trim();
+    if value.is_empty() {
+        return Some(format!("footer `{}` needs a value", footer.token));
+    }
+    let expected = match kind.value {
+        FooterValue::Text => return None,
+        FooterValue::Person if is_person(value) => return None,
+        FooterValue::Person => "`Name <email>`",
+        FooterValue::Reference if footer.separator == Separator::Hash => {
+            // `Token #value` already carries the `#`.
+            if is_reference(&format!("#{value}")) {
+                return None;
+            }
+            "an issue number"
+        }
+        FooterValue::Reference if value.split(',').all(|r| is_reference(r.trim())) => return None,
+        FooterValue::Reference => "an issue reference like `#123` or `PROJ-123`, or a commit hash",
+        FooterValue::Issue => {
+            let value = match footer.separator {
+                Separator::Hash => format!("#{value}"),
+                Separator::Colon => value.to_string(),
+            };
+            if tracker::is_issue(&value) {
+                return None;
+            }
+            if value.contains([',', ' ']) {
+                "a single issue; add the footer again for each one"
+            } else {
+                "an issue like `#123` or `group/project#123`, or a link to it"
+            }
+        }
+        FooterValue::Jira => {
+            let keys = tracker::jira_keys(value);
+            let key = keys.iter().find(|key| !tracker::is_jira_key(key))?;
+            return Some(format!(
+                "footer `{}` expects Jira issue keys like `PROJ-123`, not `{key}`",
+                footer.token
+            ));
+        }
+        FooterValue::SmartCommit => {
+            let value = match footer.separator {
+                Separator::Hash => format!("#{value}"),
+                Separator::Colon => value.to_string(),
+            };
+            let problem = tracker::SmartCommit::parse(&value).problem()?;
+            return Some(format!("footer `{}` expects {problem}", footer.token));
+        }
+    };
+    Some(format!("footer `{}` expects {expected}", footer.token))
+}
+
+/// `Name <user@host>`.
+fn is_person(value: &str) -> bool {
+    let Some((name, email)) = value.split_once('<') else {
+        return false;
+    };
+    let Some(email) = email.strip_suffix('>') else {
+        return false;
+    };
+    let valid_email = email
+        .split_once('@')
+        .is_some_and(|(user, host)| !user.is_empty() && !host.is_empty())
+        && !email.contains(|c: char| c.is_whitespace() || c == '<' || c == '>');
+    !name.trim().is_empty() && name.ends_with(' ') && valid_email
+}
+
+/// `#123`, `owner/repo#123`, `PROJ-123`, a link or an abbreviated or full
+/// commit hash.
+fn is_reference(value: &str) -> bool {
+    let digits = |text: &str| !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+    if value.starts_with("https://") || value.starts_with("http://") {
+        return !value.contains(char::is_whitespace);
+    }
+    if let Some((repo, number)) = value.rsplit_once('#') {
+        let repo_valid = repo.is_empty()
+            || repo
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'));
+        return repo_valid && digits(number);
+    }
+    if let Some((project, number)) = value.rsplit_once('-') {
+        let project_valid = !project.is_empty()
+            && project
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+            && project.starts_with(|c: char| c.is_ascii_alphabetic());
+        if project_valid && digits(number) {
+            return true;
+        }
+    }
+    (7..=40).contains(&value.len()) && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(json: &str) -> Config {
+        let mut config = Config::default();
+        config
+            .merge_json(
+                r#"{
+                    "types": [{"name": "feat"}, {"name": "fix", "required_footers": ["Refs"]}],
+                    "footers": [{"token": "Refs"}, {"token": "BREAKING CHANGE"}]
+                }"#,
+            )
+            .unwrap();
+        config.merge_json(json).unwrap();
+        config
+    }
+
+    /// The rule, line and column of each diagnostic.
+    fn found(message: &str, config: &Config) -> Vec<(&'static str, usize, usize)> {
+        check_message(message, config)
+            .into_iter()
+            .map(|d| (d.rule, d.line, d.column))
+            .collect()
+    }
+
+    #[test]
+    fn accepts_a_valid_message() {
+        let message = "fix(ui): handle resizes\n\nThe layout is recomputed.\n\nRefs: #4";
+        assert_eq!(check_message(message, &config("{}")), Vec::new());
+    }
+
+    #[test]
+    fn points_at_header_problems() {
+        let config = config(r#"{"scopes": ["ui"]}"#);
+        assert_eq!(found("chore: x", &config), vec![("type_enum", 1, 1)]);
+        assert_eq!(found("feat(db): x", &config), vec![("scope_enum", 1, 6)]);
+        assert_eq!(
+            found("feat(ui): add it.", &config),
+            vec![("description_full_stop", 1, 17)]
+        );
+        assert_eq!(
+            found("Feat: x", &config),
+            vec![("type_enum", 1, 1), ("type_lowercase", 1, 1)]
+        );
+        assert_eq!(found("feat x", &config), vec![("header_format", 1, 5)]);
+        assert_eq!(found("feat: ", &config), vec![("description_empty", 1, 6)]);
+    }
+
+    #[test]
+    fn points_at_body_and_footer_lines() {
+        let long = "x".repeat(101);
+        let message = format!("feat: x\nno blank\n{long}\n\nRefs: #1\nAcked-by: A <a@b>");
+        assert_eq!(
+            found(&message, &config("{}")),
+            vec![
+                ("body_leading_blank", 2, 1),
+                ("body_max_line_length", 3, 101),
+                ("footer_enum", 6, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_footers_on_the_last_line() {
+        assert_eq!(
+            found("fix: x\n\nSome body.", &config("{}")),
+            vec![("footer_required", 3, 1)]
+        );
+        assert_eq!(
+            found("feat!: x", &config("{}")),
+            vec![("breaking_change_description", 1, 5)]
+        );
+        assert_eq!(
+            found("feat!: x\n\nBREAKING CHANGE: gone", &config("{}")),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn severities_come_from_the_config() {
+        let config = config(r#"{"rules": {"type_enum": "off", "description_full_stop": "warn"}}"#);
+        let diagnostics = check_message("chore: x.", &config);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, Severity::Warn);
+        assert_eq!(commit_status(&diagnostics), CommitStatus::Ready);
+        assert_eq!(
+            commit_status(&check_message("feat:", &config)),
+            CommitStatus::Unready
+        );
+    }
+
+    #[test]
+    fn structured_commits_get_the_same_positions() {
+        let commit: ConventionalCommit = "feat: x\n\nLine one.\nLine two.\n\nAcked-by: A <a@b>"
+            .parse()
+            .unwrap();
+        let positions: Vec<_> = check(&commit, &config("{}"))
+            .into_iter()
+            .map(|d| (d.rule, d.line))
+            .collect();
+        assert_eq!(positions, vec![("footer_enum", 6)]);
+    }
+
+    #[test]
+    fn body_lines_that_look_like_footers_stay_in_the_body() {
+        let config =
+            config(r#"{"rules": {"body_max_line_length": {"severity": "error", "max": 20}}}"#);
+        let message =
+            "fix: x\n\nRefs: see the discussion below\nand a line that is too long\n\nRefs: #1";
+        assert_eq!(
+            found(message, &config),
+            [
+                ("body_max_line_length", 3, 21),
+                ("body_max_line_length", 4, 21)
+            ]
+        );
+    }
+
+    #[test]
+    fn recognises_people() {
+        assert!(is_person("Ada Lovelace <ada@example.com>"));
+        assert!(is_person("A <a@b>"));
+        assert!(!is_person("Ada Lovelace"));
+        assert!(!is_person("<ada@example.com>"));
+        assert!(!is_person("Ada<ada@example.com>"));
+        assert!(!is_person("Ada <ada example.com>"));
+        assert!(!is_person("Ada <@example.com>"));
+        assert!(!is_person("Ada <ada@example.com"));
+    }
+
+    #[test]
+    fn recognises_references() {
+        for reference in [
+            "#12",
+            "owner/repo#12",
+            "PROJ-123",
+            "a1b2c3d",
+            "https://example.com/issues/12",
+        ] {
+            assert!(is_reference(reference), "{reference}");
+        }
+        for value in [
+            "12",
+            "#",
+            "#12a",
+            "PROJ-",
+            "-123",
+            "a1b2c3",
+            "see #12",
+            "http://a b",
+        ] {
+            assert!(!is_reference(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn checks_footer_values_by_kind() {
+        let config = config(
+            r#"{"footers": [
+                {"token": "Refs", "value": "reference"},
+                {"token": "Signed-off-by", "value": "person"},
+                {"token": "Note"}
+            ]}"#,
+        );
+        let problem =
+            |line: &str| footer_value_problem(&Footer::parse_line(line).unwrap(), &config);
+        assert_eq!(problem("Refs: #1, PROJ-2"), None);
+        assert_eq!(problem("Refs #1"), None);
+        assert_eq!(
+            problem("Refs #abc"),
+            Some("footer `Refs` expects an issue number".to_string())
+        );
+        assert!(problem("Refs: #1, soon").is_some());
+        assert_eq!(problem("Signed-off-by: A <a@b>"), None);
+        assert_eq!(
+            problem("Signed-off-by: A"),
+            Some("footer `Signed-off-by` expects `Name <email>`".to_string())
+        );
+        assert_eq!(problem("Note: anything goes"), None);
+        assert_eq!(
+            footer_value_problem(&Footer::new("Note", " "), &config),
+            Some("footer `Note` needs a value".to_string())
+        );
+        // Unknown footers are left to `footer_enum`.
+        assert_eq!(problem("Other: x"), None);
+    }
+
+    #[test]
+    fn template_sections_last_are_read_as_footers() {
+        let config = config("{}");
+        assert_eq!(
+            found("feat: x\n\nCause: y\n\nFix: z", &config),
+            [("footer_enum", 5, 1)]
+        );
+        assert_eq!(found("feat: x\n\nFix: z\n\nRefs: #1", &config), []);
+    }
+
+    #[test]
+    fn checks_tracker_footer_values() {
+        let config = config(
+            r#"{"footers": [
+                {"token": "Closes", "value": "issue"},
+                {"token": "Jira", "value": "jira"},
+                {"token": "Smart", "value": "smart-commit"}
+            ]}"#,
+        );
+        let problem =
+            |line: &str| footer_value_problem(&Footer::parse_line(line).unwrap(), &config);
+        assert_eq!(problem("Closes #12"), None);
+        assert_eq!(problem("Closes: group/project#12"), None);
+        assert_eq!(
+            problem("Closes: #1, #2"),
+            Some(
+                "footer `Closes` expects a single issue; add the footer again for each one".into()
+            )
+        );
+        assert!(problem("Closes: soon").unwrap().contains("an issue like"));
+        assert_eq!(problem("Jira: AB-1, CD-2"), None);
+        assert_eq!(
+            problem("Jira: AB-1, cd-2"),
+            Some("footer `Jira` expects Jira issue keys like `PROJ-123`, not `cd-2`".into())
+        );
+        assert_eq!(problem("Smart: AB-1 #time 2h #close"), None);
+        assert_eq!(
+            problem("Smart: AB-1 #time"),
+            Some("footer `Smart` expects a duration like `1h 30m` after `#time`".into())
+        );
+    }
+}
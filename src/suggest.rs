@@ -0,0 +1,422 @@
+use std::path::Path;
+
+use crate::{
+    config::Config,
+    diff,
+    git::{self, GitError, StagedFile},
+    manifest::{self, Package},
+    version,
+};
+
+/// Directory names too general to make a good scope.
+const GENERIC_DIRS: [&str; 9] = [
+    "src", "lib", "crates", "packages", "tests", "test", "docs", "doc", "examples",
+];
+
+/// File stems too general to make a good scope.
+const GENERIC_STEMS: [&str; 5] = ["main", "lib", "mod", "index", "README"];
+
+/// A commit type and scope proposed from the staged changes, with the
+/// reasons for them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Suggestion {
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub reasons: Vec<String>,
+}
+
+/// What a staged file's change is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Docs,
+    Test,
+    Ci,
+    Build,
+    Other,
+}
+
+impl Kind {
+    fn commit_type(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Kind::Docs => Some(("docs", "only documentation is staged")),
+            Kind::Test => Some(("test", "only tests are staged")),
+            Kind::Ci => Some(("ci", "only CI configuration is staged")),
+            Kind::Build => Some(("build", "only dependencies and lock files are staged")),
+            Kind::Other => None,
+        }
+    }
+}
+
+/// Suggests a type and scope for the changes staged in the current repository.
+pub fn from_index(config: &Config) -> Result<Suggestion, GitError> {
+    let files = git::staged_files()?;
+    let kinds: Vec<Kind> = files.iter().map(classify).collect();
+    let packages = git::toplevel()
+        .ok()
+        .and_then(|root| manifest::packages(&root).ok())
+        .unwrap_or_default();
+    Ok(suggest(&files, &kinds, &packages, config))
+}
+
+fn suggest(
+    files: &[StagedFile],
+    kinds: &[Kind],
+    packages: &[Package],
+    config: &Config,
+) -> Suggestion {
+    let mut suggestion = Suggestion::default();
+    if files.is_empty() {
+        return suggestion;
+    }
+
+    let kind = kinds[0];
+    if let Some((commit_type, reason)) = kind.commit_type() {
+        let configured = config.types.iter().any(|t| t.name == commit_type);
+        if configured && kinds.iter().all(|k| *k == kind) {
+            suggestion.commit_type = Some(commit_type.to_string());
+            suggestion
+                .reasons
+                .push(format!("type `{commit_type}`: {reason}"));
+        }
+    }
+
+    if let Some((scope, reason)) = scope(files, packages) {
+        let configured = config.scopes.is_empty() || config.scopes.contains(&scope);
+        // `ci(ci)` says nothing more than `ci`.
+        if configured && suggestion.commit_type.as_ref() != Some(&scope) {
+            suggestion
+                .reasons
+                .push(format!("scope `{scope}`: {reason}"));
+            suggestion.scope = Some(scope);
+        }
+    }
+    suggestion
+}
+
+/// The package every staged file is in or, failing that, the name of
+/// their common directory or only file.
+fn scope(files: &[StagedFile], packages: &[Package]) -> Option<(String, String)> {
+    let owners: Vec<Option<&Package>> = files
+        .iter()
+        .map(|file| version::owner(packages, &file.path))
+        .collect();
+    if let Some(Some(package)) = owners.first() {
+        let shared = owners.iter().all(|owner| *owner == Some(*package));
+        if shared && !package.dir.as_os_str().is_empty() {
+            return Some((
+                package.name.clone(),
+                format!("every staged file is in package `{}`", package.name),
+            ));
+        }
+    }
+
+    // Files at the top, like manifests and readmes, concern the whole
+    // project rather than a part of it.
+    if let [file] = files {
+        let path = Path::new(&file.path);
+        let stem = path.file_stem()?.to_string_lossy();
+        let nested = file.path.contains('/');
+        if nested && !GENERIC_STEMS.contains(&stem.as_ref()) && !stem.starts_with('.') {
+            return Some((stem.to_string(), format!("only `{}` is staged", file.path)));
+        }
+    }
+
+    // The deepest directory holding every file, named after its last
+    // component that isn't too general.
+    let mut common: Vec<&str> = parent_components(&files[0].path);
+    for file in &files[1..] {
+        let components = parent_components(&file.path);
+        let shared = common
+            .iter()
+            .zip(&components)
+            .take_while(|(a, b)| a == b)
+            .count();
+        common.truncate(shared);
+    }
+    let name = common
+        .iter()
+        .rev()
+        .find(|component| !GENERIC_DIRS.contains(component) && !component.starts_with('.'))?;
+    Some((
+        name.to_string(),
+        format!("every staged file is under `{}/`", common.join("/")),
+    ))
+}
+
+fn parent_components(path: &str) -> Vec<&str> {
+    let mut components: Vec<&str> = path.split('/').collect();
+    components.pop();
+    components
+}
+
+fn classify(file: &StagedFile) -> Kind {
+    let path = file.path.as_str();
+    let name = path.rsplit('/').next().unwrap_or(path);
+    let extension = name
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase());
+    let components: Vec<&str> = path.split('/').collect();
+
+    let ci = [
+        ".github/",
+        ".circleci/",
+        ".buildkite/",
+        ".gitlab/",
+        ".gitlab-ci",
+    ]
+    .iter()
+    .any(|prefix| path.starts_with(prefix))
+        || [
+            ".travis.yml",
+            "azure-pipelines.yml",
+            "Jenkinsfile",
+            "bitbucket-pipelines.yml",
+        ]
+        .contains(&path);
+    if ci {
+        return Kind::Ci;
+    }
+
+    let docs = matches!(
+        extension.as_deref(),
+        Some("md" | "markdown" | "rst" | "adoc")
+    ) || matches!(components[0], "docs" | "doc");
+    if docs {
+        return Kind::Docs;
+    }
+
+    let stem = name.split('.').next().unwrap_or(name);
+    let test = components[..components.len() - 1]
+        .iter()
+        .any(|dir| matches!(*dir, "tests" | "test" | "__tests__"))
+        || stem.ends_with("_test")
+        || stem.ends_with("_spec")
+        || name.contains(".test.")
+        || name.contains(".spec.")
+        || (extension.as_deref() == Some("rs") && file.status == 'M' && only_test_code(path));
+    if test {
+        return Kind::Test;
+    }
+
+    let build = matches!(
+        name,
+        "Cargo.lock" | "package-lock.json" | "yarn.lock" | "pnpm-lock.yaml"
+    ) || (name == "Cargo.toml" && file.status == 'M' && only_dependencies(path));
+    if build {
+        return Kind::Build;
+    }
+    Kind::Other
+}
+
+/// Whether every change to a Rust file is below its `#[cfg(test)]` line.
+fn only_test_code(path: &str) -> bool {
+    match (git::show_file(None, path), git::staged_diff(path)) {
+        (Ok(contents), Ok(diff)) => changes_only_tests(&contents, &diff),
+        _ => false,
+    }
+}
+
+/// Whether every hunk of `diff` is below the `#[cfg(test)]` line of the
+/// staged `contents`.
+fn changes_only_tests(contents: &str, diff: &str) -> bool {
+    let Some(start) = contents
+        .lines()
+        .position(|line| line.trim_start().starts_with("#[cfg(test)]"))
+        .map(|index| index + 1)
+    else {
+        return false;
+    };
+    let hunks = diff::parse_hunks(diff);
+    !hunks.is_empty()
+        && hunks.iter().all(|hunk| {
+            // Removed lines sit after `new_start` in the new file.
+            hunk.added().iter().all(|line| *line > start)
+                && (hunk.removed().is_empty() || hunk.new_start >= start)
+        })
+}
+
+/// Whether every change to a `Cargo.toml` is in its dependency tables.
+fn only_dependencies(path: &str) -> bool {
+    match (
+        git::show_file(Some("HEAD"), path),
+        git::show_file(None, path),
+        git::staged_diff(path),
+    ) {
+        (Ok(old), Ok(new), Ok(diff)) => changes_only_dependencies(&old, &new, &diff),
+        _ => false,
+    }
+}
+
+/// Whether every hunk of `diff` between the `old` and `new` manifest is in
+/// a dependency table.
+fn changes_only_dependencies(old: &str, new: &str, diff: &str) -> bool {
+    let hunks = diff::parse_hunks(diff);
+    !hunks.is_empty()
+        && hunks.iter().all(|hunk| {
+            hunk.removed()
+                .iter()
+                .all(|line| manifest::is_dependency_line(old, *line))
+                && hunk
+                    .added()
+                    .iter()
+                    .all(|line| manifest::is_dependency_line(new, *line))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::PackageKind;
+
+    fn staged(path: &str, status: char) -> StagedFile {
+        StagedFile {
+            status,
+            path: path.to_string(),
+            old_path: None,
+        }
+    }
+
+    fn package(name: &str, dir: &str) -> Package {
+        Package {
+            name: name.to_string(),
+            kind: PackageKind::Cargo,
+            dir: dir.into(),
+            manifest: Path::new(dir).join("Cargo.toml"),
+            version: None,
+            dependencies: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn classifies_files_by_path() {
+        // Added files are classified by path alone.
+        for (path, status, kind) in [
+            (".github/workflows/ci.yml", 'M', Kind::Ci),
+            (".gitlab-ci.yml", 'M', Kind::Ci),
+            ("Jenkinsfile", 'A', Kind::Ci),
+            ("README.md", 'M', Kind::Docs),
+            ("guide/intro.MD", 'A', Kind::Docs),
+            ("docs/api.txt", 'A', Kind::Docs),
+            ("tests/cli.rs", 'M', Kind::Test),
+            ("src/__tests__/app.js", 'A', Kind::Test),
+            ("pkg/parser_test.go", 'A', Kind::Test),
+            ("web/app.spec.ts", 'M', Kind::Test),
+            ("Cargo.lock", 'M', Kind::Build),
+            ("web/yarn.lock", 'A', Kind::Build),
+            ("Cargo.toml", 'A', Kind::Other),
+            ("src/testing.rs", 'A', Kind::Other),
+            ("src/lib.rs", 'D', Kind::Other),
+        ] {
+            assert_eq!(classify(&staged(path, status)), kind, "{path}");
+        }
+    }
+
+    #[test]
+    fn scopes_by_package_file_or_directory() {
+        let packages = [package("root", ""), package("cli", "crates/cli")];
+        let scope = |paths: &[&str]| {
+            let files: Vec<StagedFile> = paths.iter().map(|path| staged(path, 'M')).collect();
+            scope(&files, &packages)
+        };
+        for (paths, expected) in [
+            (
+                &["crates/cli/src/main.rs", "crates/cli/Cargo.toml"][..],
+                Some("cli"),
+            ),
+            (&["src/parser/lexer.rs"], Some("lexer")),
+            (
+                &["src/parser/lexer.rs", "src/parser/mod.rs"],
+                Some("parser"),
+            ),
+            (&["src/parser/mod.rs"], Some("parser")),
+            (&["crates/cli/src/main.rs", "crates/core/src/lib.rs"], None),
+            (&["src/lib.rs"], None),
+            (&["Cargo.toml"], None),
+            (&[".config/tool.toml"], Some("tool")),
+            (&["src/ui/view.rs", "tests/ui.rs"], None),
+        ] {
+            assert_eq!(
+                scope(paths).map(|(scope, _)| scope).as_deref(),
+                expected,
+                "{paths:?}"
+            );
+        }
+        assert_eq!(
+            scope(&["src/parser/a.rs", "src/parser/b.rs"]).unwrap().1,
+            "every staged file is under `src/parser/`"
+        );
+    }
+
+    #[test]
+    fn suggests_configured_types_and_scopes() {
+        let mut config = Config::default();
+        config
+            .merge_json(r#"{"types": [{"name": "feat"}, {"name": "docs"}, {"name": "ci"}]}"#)
+            .unwrap();
+        let suggest = |paths: &[&str], config: &Config| {
+            let files: Vec<StagedFile> = paths.iter().map(|path| staged(path, 'A')).collect();
+            let kinds: Vec<Kind> = files.iter().map(classify).collect();
+            let suggestion = suggest(&files, &kinds, &[], config);
+            (suggestion.commit_type, suggestion.scope)
+        };
+        let some = |text: &str| Some(text.to_string());
+        assert_eq!(suggest(&[], &config), (None, None));
+        assert_eq!(
+            suggest(&["README.md", "docs/a.md"], &config),
+            (some("docs"), None)
+        );
+        assert_eq!(
+            suggest(&["src/api/a.rs", "src/api/v1/b.rs"], &config),
+            (None, some("api"))
+        );
+        // `test` isn't configured.
+        assert_eq!(suggest(&["tests/cli.rs"], &config), (None, some("cli")));
+        // The scope would repeat the type.
+        assert_eq!(
+            suggest(&[".github/workflows/ci.yml"], &config),
+            (some("ci"), None)
+        );
+
+        config.merge_json(r#"{"scopes": ["api"]}"#).unwrap();
+        assert_eq!(suggest(&["src/parser/lexer.rs"], &config), (None, None));
+    }
+
+    #[test]
+    fn checks_that_changes_are_in_test_code() {
+        let contents = "fn a() {}\n\nfn b() {}\n\n#[cfg(test)]\nmod tests {\n    fn t() {}\n}\n";
+        for (diff, expected) in [
+            ("@@ -6,0 +7 @@\n+    fn t() {}\n", true),
+            ("@@ -7 +6,0 @@\n-    fn old() {}\n", true),
+            ("@@ -1 +1 @@\n-fn a() {}\n+fn a() { }\n", false),
+            (
+                "@@ -1 +1 @@\n-fn a() {}\n+fn a() { }\n@@ -6,0 +7 @@\n+    fn t() {}\n",
+                false,
+            ),
+            ("", false),
+        ] {
+            assert_eq!(changes_only_tests(contents, diff), expected, "{diff}");
+        }
+        assert!(!changes_only_tests(
+            "fn t() {}\n",
+            "@@ -1 +1 @@\n-x\n+fn t() {}\n"
+        ));
+    }
+
+    #[test]
+    fn checks_that_changes_are_in_dependencies() {
+        let old = "[package]\nname = \"a\"\nversion = \"1.0.0\"\n\n[dependencies]\nserde = \"1\"\n";
+        let bumped = old.replace("serde = \"1\"", "serde = \"1.0.200\"");
+        assert!(changes_only_dependencies(
+            old,
+            &bumped,
+            "@@ -6 +6 @@\n-serde = \"1\"\n+serde = \"1.0.200\"\n"
+        ));
+        let released = old.replace("1.0.0", "1.1.0");
+        assert!(!changes_only_dependencies(
+            old,
+            &released,
+            "@@ -3 +3 @@\n-version = \"1.0.0\"\n+version = \"1.1.0\"\n"
+        ));
+        assert!(!changes_only_dependencies(old, old, ""));
+    }
+}
@@ -0,0 +1,131 @@
+use crate::{commit::Footer, git};
+
+/// The values template placeholders expand to. Missing ones expand to
+/// nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Placeholders {
+    pub branch: Option<String>,
+    pub ticket: Option<String>,
+    pub staged_files: Vec<String>,
+}
+
+impl Placeholders {
+    /// The current branch and staged files, with the ticket found for
+    /// the branch.
+    pub fn current(ticket: Option<String>) -> Self {
+        Placeholders {
+            branch: git::current_branch(),
+            ticket,
+            staged_files: git::staged_files()
+                .map(|files| files.into_iter().map(|file| file.path).collect())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+/// Replaces `{branch}`, `{ticket}` and `{staged_files}` in a template.
+///
+/// A line with `{staged_files}` is repeated for each file, so `- {staged_files}`
+/// makes a list, and dropped when nothing is staged.
+pub fn expand(template: &str, placeholders: &Placeholders) -> String {
+    let branch = placeholders.branch.as_deref().unwrap_or_default();
+    let ticket = placeholders.ticket.as_deref().unwrap_or_default();
+    let mut lines = Vec::new();
+    for line in template.lines() {
+        let line = line.replace("{branch}", branch).replace("{ticket}", ticket);
+        if line.contains("{staged_files}") {
+            lines.extend(
+                placeholders
+                    .staged_files
+                    .iter()
+                    .map(|file| line.replace("{staged_files}", file)),
+            );
+        } else {
+            lines.push(line);
+        }
+    }
+    lines.join("\n")
+}
+
+/// The first line starting a paragraph that git would read as a trailer
+/// once text is written after it, like `Testing:`. Filled in last, such a
+/// section would end up among the footers.
+pub fn trailer_heading(template: &str) -> Option<&str> {
+    let sample = Placeholders {
+        branch: Some("main".to_string()),
+        ticket: Some("PROJ-1".to_string()),
+        staged_files: vec!["file".to_string()],
+    };
+    let mut starts_paragraph = true;
+    for line in template.lines() {
+        let filled = format!("{} text", expand(line, &sample));
+        if starts_paragraph && Footer::parse_line(&filled).is_some() {
+            return Some(line);
+        }
+        starts_paragraph = line.trim().is_empty();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commit::ConventionalCommit;
+
+    fn placeholders(staged_files: &[&str]) -> Placeholders {
+        Placeholders {
+            branch: Some("fix/PROJ-7-crash".to_string()),
+            ticket: Some("PROJ-7".to_string()),
+            staged_files: staged_files.iter().map(|file| file.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn expands_branch_and_ticket() {
+        assert_eq!(
+            expand("On {branch}, for {ticket} ({ticket}).", &placeholders(&[])),
+            "On fix/PROJ-7-crash, for PROJ-7 (PROJ-7)."
+        );
+        assert_eq!(
+            expand("For {ticket}.\n{unknown}", &Placeholders::default()),
+            "For .\n{unknown}"
+        );
+    }
+
+    #[test]
+    fn repeats_lines_for_staged_files() {
+        let template = "Changed:\n- {staged_files} on {branch}\n\nDone.";
+        assert_eq!(
+            expand(template, &placeholders(&["a.rs", "b.rs"])),
+            "Changed:\n- a.rs on fix/PROJ-7-crash\n- b.rs on fix/PROJ-7-crash\n\nDone."
+        );
+        assert_eq!(expand(template, &placeholders(&[])), "Changed:\n\nDone.");
+    }
+
+    #[test]
+    fn finds_headings_that_read_as_trailers() {
+        assert_eq!(
+            trailer_heading("Root cause:\n\nFix:\n\nTesting:"),
+            Some("Fix:")
+        );
+        assert_eq!(trailer_heading("Fixes #{ticket}"), Some("Fixes #{ticket}"));
+        assert_eq!(trailer_heading("{ticket}: done"), Some("{ticket}: done"));
+        // Only a paragraph's first line can start the footers.
+        assert_eq!(trailer_heading("Before\nTime: 10ms"), None);
+        assert_eq!(trailer_heading("Root cause:\n\nFix\n\nTesting"), None);
+    }
+
+    #[test]
+    fn filled_in_sections_stay_in_the_body() {
+        let body = expand("Root cause:\n\nFix\n\nTesting", &placeholders(&[]));
+        let body = body
+            .replace("Root cause:", "Root cause: a race on {ticket}")
+            .replace("Fix", "Fix\nLock the cache.")
+            .replace("Testing", "Testing\nRan cargo test.");
+        let message = format!("fix: x\n\n{body}");
+        let commit: ConventionalCommit = message.parse().unwrap();
+        assert_eq!(commit.body.as_deref(), Some(body.as_str()));
+        assert!(commit.footers.is_empty());
+        assert_eq!(commit.to_string(), message);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::{
+    commit::{Footer, Separator},
+    config::FooterValue,
+};
+
+/// A single issue as GitHub and GitLab closing keywords take it: `#123`,
+/// `owner/repo#123`, `group/subgroup/project#123` or a link.
+pub fn is_issue(value: &str) -> bool {
+    if value.starts_with("https://") || value.starts_with("http://") {
+        return !value.contains(char::is_whitespace);
+    }
+    let Some((project, number)) = value.rsplit_once('#') else {
+        return false;
+    };
+    project
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '/' | '-' | '_' | '.'))
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// A Jira issue key: an upper-case project key, a dash and a number, as
+/// in `PROJ-123`.
+pub fn is_jira_key(value: &str) -> bool {
+    let Some((project, number)) = value.rsplit_once('-') else {
+        return false;
+    };
+    project.len() >= 2
+        && project.starts_with(|c: char| c.is_ascii_uppercase())
+        && project
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+        && !number.is_empty()
+        && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Jira issue keys separated by commas or spaces.
+pub fn jira_keys(value: &str) -> Vec<&str> {
+    value
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|key| !key.is_empty())
+        .collect()
+}
+
+/// A Jira smart commit, `PROJ-123 #time 1h 30m #comment Done`: the issues
+/// followed by commands and their arguments. Any command but `#time` and
+/// `#comment` moves the issues through the workflow transition it names.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartCommit<'a> {
+    pub keys: Vec<&'a str>,
+    pub commands: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> SmartCommit<'a> {
+    /// Splits a smart commit into issues and commands. Like Jira, every
+    /// `#` after a space starts a new command.
+    pub fn parse(value: &'a str) -> Self {
+        let (keys, commands) = match value.find('#') {
+            Some(start) => (&value[..start], &value[start..]),
+            None => (value, ""),
+        };
+        let mut starts: Vec<usize> = commands
+            .char_indices()
+            .filter(|(i, c)| {
+                *c == '#' && (*i == 0 || commands[..*i].ends_with(char::is_whitespace))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        starts.push(commands.len());
+        let commands = starts
+            .windows(2)
+            .map(|range| {
+                let command = commands[range[0] + 1..range[1]].trim();
+                command
+                    .split_once(char::is_whitespace)
+                    .map_or((command, ""), |(name, arguments)| (name, arguments.trim()))
+            })
+            .collect();
+        SmartCommit {
+            keys: jira_keys(keys),
+            commands,
+        }
+    }
+
+    /// What Jira would reject, if anything.
+    pub fn problem(&self) -> Option<String> {
+        if self.keys.is_empty() {
+            return Some("issue keys before the commands, like `PROJ-123 #comment Done`".into());
+        }
+        if let Some(key) = self.keys.iter().find(|key| !is_jira_key(key)) {
+            return Some(format!("`{key}` to be a Jira issue key like `PROJ-123`"));
+        }
+        if self.commands.is_empty() {
+            return Some("a command like `#comment`, `#time` or a transition".into());
+        }
+        self.commands
+            .iter()
+            .find_map(|(name, arguments)| match *name {
+                "" => Some("a command name after `#`".to_string()),
+                "time" if !starts_with_duration(arguments) => {
+                    Some("a duration like `1h 30m` after `#time`".to_string())
+                }
+                "comment" if arguments.is_empty() => {
+                    Some("the text of the comment after `#comment`".to_string())
+                }
+                _ => None,
+            })
+    }
+}
+
+/// `#time` arguments start with one or more of `1w`, `2d`, `4h`, `30m`.
+fn starts_with_duration(arguments: &str) -> bool {
+    arguments.split_whitespace().next().is_some_and(|amount| {
+        amount
+            .strip_suffix(['w', 'd', 'h', 'm'])
+            .and_then(|number| number.parse::<f32>().ok())
+            .is_some_and(|number| number > 0.0)
+    })
+}
+
+/// A footer written the way its tracker reads it: single issues as
+/// `Closes #123`, Jira keys in upper case and smart commit commands in
+/// lower case. Values the tracker wouldn't accept anyway are kept as they
+/// are, so the problem can be pointed out.
+pub fn format(mut footer: Footer, kind: FooterValue) -> Footer {
+    let value = match footer.separator {
+        Separator::Hash => format!("#{}", footer.value.trim()),
+        Separator::Colon => footer.value.trim().to_string(),
+    };
+    match kind {
+        FooterValue::Issue if value.starts_with('#') && is_issue(&value) => {
+            footer.value = value[1..].to_string();
+            footer.separator = Separator::Hash;
+        }
+        FooterValue::Jira => {
+            let keys: Vec<String> = jira_keys(&value)
+                .iter()
+                .map(|key| key.to_ascii_uppercase())
+                .collect();
+            if !keys.is_empty() && keys.iter().all(|key| is_jira_key(key)) {
+                footer.value = keys.join(", ");
+                footer.separator = Separator::Colon;
+            }
+        }
+        FooterValue::SmartCommit => {
+            let smart = SmartCommit::parse(&value);
+            let mut formatted = smart
+                .keys
+                .iter()
+                .map(|key| key.to_ascii_uppercase())
+                .collect::<Vec<_>>()
+                .join(", ");
+            for (name, arguments) in &smart.commands {
+                formatted.push_str(&format!(" #{}", name.to_lowercase()));
+                if !arguments.is_empty() {
+                    formatted.push(' ');
+                    formatted.push_str(arguments);
+                }
+            }
+            if SmartCommit::parse(&formatted).problem().is_none() {
+                footer.value = formatted;
+                footer.separator = Separator::Colon;
+            }
+        }
+        _ => {}
+    }
+    footer
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognises_issues() {
+        for value in [
+            "#12",
+            "owner/repo#3",
+            "group/sub.group/my_project#45",
+            "https://example.com/issues/6",
+        ] {
+            assert!(is_issue(value), "{value}");
+        }
+        for value in ["12", "#", "#12a", "owner repo#3", "https://example.com/a b"] {
+            assert!(!is_issue(value), "{value}");
+        }
+    }
+
+    #[test]
+    fn recognises_jira_keys() {
+        for value in ["PROJ-1", "AB2-34", "MY_APP-5"] {
+            assert!(is_jira_key(value), "{value}");
+        }
+        for value in ["P-1", "proj-1", "2AB-3", "PROJ-", "PROJ-1a", "PROJ"] {
+            assert!(!is_jira_key(value), "{value}");
+        }
+        assert_eq!(jira_keys("A-1, B-2 C-3,,"), ["A-1", "B-2", "C-3"]);
+    }
+
+    #[test]
+    fn parses_smart_commits() {
+        let smart = SmartCommit::parse("AB-1, AB-2 #time 1h 30m #comment Fixed #3 #resolve");
+        assert_eq!(smart.keys, ["AB-1", "AB-2"]);
+        assert_eq!(
+            smart.commands,
+            [
+                ("time", "1h 30m"),
+                ("comment", "Fixed"),
+                ("3", ""),
+                ("resolve", "")
+            ]
+        );
+        assert_eq!(smart.problem(), None);
+        let smart = SmartCommit::parse("AB-1 #comment see issue#4");
+        assert_eq!(smart.commands, [("comment", "see issue#4")]);
+    }
+
+    #[test]
+    fn points_at_smart_commit_problems() {
+        let problem = |value| SmartCommit::parse(value).problem();
+        assert_eq!(problem("AB-1 #close"), None);
+        assert_eq!(problem("AB-1 #time 0.5d"), None);
+        assert!(problem("#comment Done").unwrap().starts_with("issue keys"));
+        assert!(problem("ab-1 #close").unwrap().contains("`ab-1`"));
+        assert!(problem("AB-1").unwrap().starts_with("a command"));
+        assert!(problem("AB-1 #").unwrap().contains("command name"));
+        assert!(problem("AB-1 #time soon").unwrap().contains("duration"));
+        assert!(problem("AB-1 #time 0h").unwrap().contains("duration"));
+        assert!(problem("AB-1 #comment").unwrap().contains("comment"));
+    }
+
+    #[test]
+    fn formats_footers_for_their_tracker() {
+        let format = |line: &str, kind| format(Footer::parse_line(line).unwrap(), kind).to_string();
+        assert_eq!(format("Closes: #12", FooterValue::Issue), "Closes #12");
+        assert_eq!(
+            format("Closes: o/r#12", FooterValue::Issue),
+            "Closes: o/r#12"
+        );
+        assert_eq!(format("Closes: 12", FooterValue::Issue), "Closes: 12");
+        assert_eq!(
+            format("Jira: ab-1 cd-2", FooterValue::Jira),
+            "Jira: AB-1, CD-2"
+        );
+        assert_eq!(
+            format("Jira: ab-1 soon", FooterValue::Jira),
+            "Jira: ab-1 soon"
+        );
+        assert_eq!(
+            format(
+                "Smart: ab-1 #Time 1h #COMMENT Done",
+                FooterValue::SmartCommit
+            ),
+            "Smart: AB-1 #time 1h #comment Done"
+        );
+        assert_eq!(
+            format("Smart: ab-1 #time", FooterValue::SmartCommit),
+            "Smart: ab-1 #time"
+        );
+        assert_eq!(format("Note: #12", FooterValue::Text), "Note: #12");
+    }
+}
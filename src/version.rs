@@ -0,0 +1,613 @@
+use std::{cmp::Ordering, fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::{
+    changelog::Changelog,
+    commit::ConventionalCommit,
+    config::Config,
+    git::{self, GitError, LogEntry},
+    manifest::Package,
+};
+
+/// A semantic version. Build metadata is accepted when parsing but dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// Pre-release identifiers such as `rc.1`, without the leading `-`.
+    pub pre: Option<String>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid version `{0}`, expected MAJOR.MINOR.PATCH[-PRERELEASE]")]
+pub struct VersionError(pub String);
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+            pre: None,
+        }
+    }
+
+    /// The version without its pre-release part.
+    pub fn release(&self) -> Version {
+        Version::new(self.major, self.minor, self.patch)
+    }
+
+    /// Applies a bump to a release. Below 1.0.0, breaking changes only bump
+    /// the minor version.
+    pub fn bump(&self, bump: Bump) -> Version {
+        let Version {
+            major,
+            minor,
+            patch,
+            ..
+        } = *self;
+        match bump {
+            Bump::None => self.release(),
+            Bump::Patch => Version::new(major, minor, patch + 1),
+            Bump::Minor => Version::new(major, minor + 1, 0),
+            Bump::Major if major == 0 => Version::new(0, minor + 1, 0),
+            Bump::Major => Version::new(major + 1, 0, 0),
+        }
+    }
+
+    /// The channel and number of a `channel.N` pre-release.
+    pub fn channel(&self) -> Option<(&str, u64)> {
+        let (channel, number) = self.pre.as_deref()?.rsplit_once('.')?;
+        Some((channel, number.parse().ok()?))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{pre}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || VersionError(s.to_string());
+        let s_without_build = s.split_once('+').map_or(s, |(version, _)| version);
+        let (release, pre) = match s_without_build.split_once('-') {
+            Some((release, pre)) => (release, Some(pre)),
+            None => (s_without_build, None),
+        };
+
+        let numbers = release
+            .split('.')
+            .map(|part| {
+                // Leading zeros aren't allowed by semver.
+                if part.is_empty() || (part.len() > 1 && part.starts_with('0')) {
+                    return None;
+                }
+                part.parse::<u64>().ok()
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(invalid)?;
+        let [major, minor, patch] = numbers[..] else {
+            return Err(invalid());
+        };
+
+        let pre_invalid = pre.is_some_and(|pre| {
+            pre.split('.').any(|identifier| {
+                identifier.is_empty()
+                    || !identifier
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            })
+        });
+        if pre_invalid {
+            return Err(invalid());
+        }
+
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            pre: pre.map(str::to_string),
+        })
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.pre, &other.pre) {
+                (None, None) => Ordering::Equal,
+                // A pre-release comes before its release.
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre(a, b),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compares pre-releases identifier by identifier: numbers numerically and
+/// below names, which compare as text.
+fn compare_pre(a: &str, b: &str) -> Ordering {
+    let mut a = a.split('.');
+    let mut b = b.split('.');
+    loop {
+        let ordering = match (a.next(), b.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+/// How much a set of changes moves the version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Bump {
+    /// Nothing worth releasing.
+    #[default]
+    None,
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    /// Major for breaking changes, minor for `feat` and patch for `fix` and `perf`.
+    pub fn of(commit: &ConventionalCommit) -> Bump {
+        if commit.is_breaking() {
+            return Bump::Major;
+        }
+        match commit.commit_type.as_str() {
+            "feat" => Bump::Minor,
+            "fix" | "perf" => Bump::Patch,
+            _ => Bump::None,
+        }
+    }
+
+    /// The largest bump of any commit in a changelog.
+    pub fn of_changelog(changelog: &Changelog) -> Bump {
+        changelog
+            .commits
+            .iter()
+            .map(|(_, commit)| Bump::of(commit))
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Bump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Bump::None => "none",
+            Bump::Patch => "patch",
+            Bump::Minor => "minor",
+            Bump::Major => "major",
+        })
+    }
+}
+
+/// A tag whose name ends in a semantic version, like `v1.2.0` or `api@0.3.1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub name: String,
+    /// Everything before the version, e.g. `v`.
+    pub prefix: String,
+    pub version: Version,
+}
+
+impl Tag {
+    pub fn new(prefix: &str, version: Version) -> Self {
+        Self {
+            name: format!("{prefix}{version}"),
+            prefix: prefix.to_string(),
+            version,
+        }
+    }
+
+    /// Splits a tag name into its prefix and version, if it has one.
+    pub fn parse(name: &str) -> Option<Tag> {
+        name.char_indices()
+            .filter(|(i, c)| {
+                c.is_ascii_digit() && !name[..*i].ends_with(|c: char| c.is_ascii_digit())
+            })
+            .find_map(|(i, _)| {
+                let version = name[i..].parse().ok()?;
+                Some(Tag {
+                    name: name.to_string(),
+                    prefix: name[..i].to_string(),
+                    version,
+                })
+            })
+    }
+}
+
+/// The tag prefix already in use: `v` if any semver tag has it, no prefix
+/// if only bare versions are tagged, and `v` for a repository without tags.
+pub fn default_prefix() -> Result<String, GitError> {
+    let prefixes: Vec<String> = git::tags("HEAD")?
+        .iter()
+        .filter_map(|name| Tag::parse(name))
+        .map(|tag| tag.prefix)
+        .collect();
+    let bare = prefixes.iter().any(String::is_empty) && !prefixes.iter().any(|p| p == "v");
+    Ok(if bare { String::new() } else { "v".to_string() })
+}
+
+/// Semver tags reachable from `HEAD` with the given prefix, oldest first.
+pub fn tags(prefix: &str) -> Result<Vec<Tag>, GitError> {
+    let mut tags: Vec<Tag> = git::tags("HEAD")?
+        .iter()
+        .filter_map(|name| Tag::parse(name))
+        .filter(|tag| tag.prefix == prefix)
+        .collect();
+    tags.sort_by(|a, b| a.version.cmp(&b.version));
+    Ok(tags)
+}
+
+/// The next release worked out from the history.
+#[derive(Debug, Clone)]
+pub struct Recommendation {
+    /// The latest release or pre-release.
+    pub previous: Option<Tag>,
+    pub bump: Bump,
+    pub next: Tag,
+    /// Commits since `previous`.
+    pub changelog: Changelog,
+}
+
+/// Works out the next version from the commits since the last release.
+///
+/// The bump is measured from the latest stable release, so a run of
+/// pre-releases all lead up to the same version. With a `pre` channel the
+/// result is `X.Y.Z-channel.N`, counting up from the previous pre-release
+/// of that channel. Returns `None` when there is nothing to release.
+pub fn recommend(
+    prefix: &str,
+    pre: Option<&str>,
+    config: &Config,
+) -> Result<Option<Recommendation>, GitError> {
+    plan(prefix, pre, None, Bump::None, config, &|_, _| true)
+}
+
+/// Like [`recommend`], for the commits `keep` accepts. Without a stable
+/// tag, `base` is bumped instead of 0.0.0, and the bump is at least
+/// `at_least`.
+fn plan(
+    prefix: &str,
+    pre: Option<&str>,
+    base: Option<&Version>,
+    at_least: Bump,
+    config: &Config,
+    keep: &dyn Fn(&LogEntry, Option<&ConventionalCommit>) -> bool,
+) -> Result<Option<Recommendation>, GitError> {
+    let tags = tags(prefix)?;
+    let previous = tags.last().cloned();
+    let stable = tags.iter().rev().find(|tag| tag.version.pre.is_none());
+    let changes_since = |tag: Option<&Tag>| -> Result<Changelog, GitError> {
+        // Without a release yet, the whole history counts.
+        let range = tag.map_or("HEAD".to_string(), |tag| format!("{}..HEAD", tag.name));
+        let mut changelog = Changelog::new(git::log(&range)?, config);
+        changelog.retain(keep);
+        Ok(changelog)
+    };
+
+    let since_stable = changes_since(stable)?;
+    let bump = Bump::of_changelog(&since_stable).max(at_least);
+    let base = match stable {
+        Some(tag) => tag.version.clone(),
+        None => base.map_or(Version::new(0, 0, 0), Version::release),
+    };
+    let release = base.bump(bump);
+
+    let version = match pre {
+        Some(channel) => {
+            let number = previous
+                .as_ref()
+                .filter(|tag| tag.version.release() == release)
+                .and_then(|tag| tag.version.channel())
+                .filter(|(previous_channel, _)| *previous_channel == channel)
+                .map_or(1, |(_, number)| number + 1);
+            Version {
+                pre: Some(format!("{channel}.{number}")),
+                ..release
+            }
+        }
+        None => release,
+    };
+
+    // A pre-release lists what changed since the one before it, a release
+    // everything since the last release.
+    let changelog = match &previous {
+        Some(tag) if pre.is_some() && Some(tag) != stable => changes_since(Some(tag))?,
+        _ => since_stable,
+    };
+
+    // A pre-release can be promoted without new commits, but another
+    // pre-release needs something new in it, unless it's forced.
+    let unchanged = previous.as_ref().is_some_and(|tag| tag.version >= version)
+        || (pre.is_some()
+            && at_least == Bump::None
+            && changelog.commits.is_empty()
+            && changelog.other.is_empty());
+    if bump == Bump::None || unchanged {
+        return Ok(None);
+    }
+    Ok(Some(Recommendation {
+        previous,
+        bump,
+        next: Tag::new(prefix, version),
+        changelog,
+    }))
+}
+
+/// A package's release in a repository that versions packages separately.
+#[derive(Debug, Clone)]
+pub struct PackageRelease {
+    pub package: Package,
+    pub recommendation: Recommendation,
+    /// Released packages this one depends on, with their new versions.
+    pub dependencies: Vec<(String, Version)>,
+}
+
+impl PackageRelease {
+    /// The changelog section for the release, with the updated dependencies
+    /// listed after the commits.
+    pub fn render(&self, date: Option<&str>, config: &Config) -> String {
+        let mut section = self.recommendation.changelog.render(
+            &self.recommendation.next.name,
+            date,
+            config,
+            false,
+        );
+        if !self.dependencies.is_empty() {
+            section.push_str("\n### Dependencies\n\n");
+            for (name, version) in &self.dependencies {
+                section.push_str(&format!("- **{name}:** updated to {version}\n"));
+            }
+        }
+        section
+    }
+}
+
+/// The package a changed path belongs to: the one with the deepest
+/// directory containing it.
+pub fn owner<'a>(packages: &'a [Package], path: &str) -> Option<&'a Package> {
+    packages
+        .iter()
+        .filter(|package| package.contains(path))
+        .max_by_key(|package| package.dir.components().count())
+}
+
+/// Whether a commit counts for a package: it changes files in it or its
+/// scope is the package name.
+pub fn concerns(
+    packages: &[Package],
+    package: &Package,
+    entry: &LogEntry,
+    commit: Option<&ConventionalCommit>,
+) -> bool {
+    commit.and_then(|commit| commit.scope.as_deref()) == Some(package.name.as_str())
+        || entry
+            .paths
+            .iter()
+            .any(|path| owner(packages, path) == Some(package))
+}
+
+/// Works out the next version of each package, tagged `name@X.Y.Z`, from
+/// the commits that [`concern`](concerns) it. Packages that depend on a released package are
+/// released too, with at least a patch bump.
+pub fn recommend_packages(
+    packages: &[Package],
+    pre: Option<&str>,
+    config: &Config,
+) -> Result<Vec<PackageRelease>, GitError> {
+    let mut releases: Vec<PackageRelease> = Vec::new();
+    let mut at_least = Bump::None;
+    loop {
+        let mut added = false;
+        for package in packages {
+            if releases.iter().any(|release| release.package == *package) {
+                continue;
+            }
+            let depends_on_release = package.dependencies.iter().any(|dependency| {
+                releases
+                    .iter()
+                    .any(|release| release.package.name == *dependency)
+            });
+            // Later rounds only pick up packages whose dependencies changed.
+            if at_least != Bump::None && !depends_on_release {
+                continue;
+            }
+            let keep = |entry: &LogEntry, commit: Option<&ConventionalCommit>| {
+                concerns(packages, package, entry, commit)
+            };
+            let prefix = format!("{}@", package.name);
+            let bump = if depends_on_release {
+                Bump::Patch
+            } else {
+                at_least
+            };
+            if let Some(recommendation) =
+                plan(&prefix, pre, package.version.as_ref(), bump, config, &keep)?
+            {
+                releases.push(PackageRelease {
+                    package: package.clone(),
+                    recommendation,
+                    dependencies: Vec::new(),
+                });
+                added = true;
+            }
+        }
+        if !added {
+            break;
+        }
+        at_least = Bump::Patch;
+    }
+
+    // Keep the order the packages were found in.
+    releases.sort_by_key(|release| packages.iter().position(|p| *p == release.package));
+    let versions: Vec<(String, Version)> = releases
+        .iter()
+        .map(|release| {
+            (
+                release.package.name.clone(),
+                release.recommendation.next.version.clone(),
+            )
+        })
+        .collect();
+    for release in &mut releases {
+        release.dependencies = versions
+            .iter()
+            .filter(|(name, _)| release.package.dependencies.contains(name))
+            .cloned()
+            .collect();
+    }
+    Ok(releases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(text: &str) -> Version {
+        text.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_versions() {
+        assert_eq!(version("1.2.3"), Version::new(1, 2, 3));
+        assert_eq!(version("1.2.3-rc.1").pre.as_deref(), Some("rc.1"));
+        assert_eq!(version("1.2.3+build.5"), Version::new(1, 2, 3));
+        assert_eq!(version("1.2.3-rc.1+build").to_string(), "1.2.3-rc.1");
+        for invalid in [
+            "1.2",
+            "1.2.3.4",
+            "01.2.3",
+            "1.2.x",
+            "1.2.3-",
+            "1.2.3-rc..1",
+            "1.2.3-r_c",
+        ] {
+            assert!(invalid.parse::<Version>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn orders_pre_releases_before_releases() {
+        let mut versions: Vec<Version> = [
+            "1.0.0",
+            "1.0.0-rc.10",
+            "1.0.0-alpha",
+            "0.9.9",
+            "1.0.0-rc.2",
+            "1.0.0-alpha.1",
+            "1.0.0-beta",
+        ]
+        .into_iter()
+        .map(version)
+        .collect();
+        versions.sort();
+        let sorted: Vec<String> = versions.iter().map(Version::to_string).collect();
+        assert_eq!(
+            sorted,
+            [
+                "0.9.9",
+                "1.0.0-alpha",
+                "1.0.0-alpha.1",
+                "1.0.0-beta",
+                "1.0.0-rc.2",
+                "1.0.0-rc.10",
+                "1.0.0",
+            ]
+        );
+    }
+
+    #[test]
+    fn bumps_releases() {
+        let current = version("1.2.3-rc.1");
+        assert_eq!(current.bump(Bump::None), Version::new(1, 2, 3));
+        assert_eq!(current.bump(Bump::Patch), Version::new(1, 2, 4));
+        assert_eq!(current.bump(Bump::Minor), Version::new(1, 3, 0));
+        assert_eq!(current.bump(Bump::Major), Version::new(2, 0, 0));
+        // Breaking changes before 1.0.0 bump the minor version.
+        assert_eq!(version("0.4.1").bump(Bump::Major), Version::new(0, 5, 0));
+    }
+
+    #[test]
+    fn reads_pre_release_channels() {
+        assert_eq!(version("1.0.0-rc.3").channel(), Some(("rc", 3)));
+        assert_eq!(version("1.0.0-beta").channel(), None);
+        assert_eq!(version("1.0.0").channel(), None);
+    }
+
+    #[test]
+    fn bumps_by_commit() {
+        let bump = |message: &str| Bump::of(&message.parse().unwrap());
+        assert_eq!(bump("fix: x"), Bump::Patch);
+        assert_eq!(bump("perf: x"), Bump::Patch);
+        assert_eq!(bump("feat: x"), Bump::Minor);
+        assert_eq!(bump("docs: x"), Bump::None);
+        assert_eq!(bump("docs!: x"), Bump::Major);
+        assert_eq!(bump("fix: x\n\nBREAKING CHANGE: y"), Bump::Major);
+    }
+
+    #[test]
+    fn splits_tags() {
+        let tag = Tag::parse("v1.2.0").unwrap();
+        assert_eq!(
+            (tag.prefix.as_str(), tag.version),
+            ("v", Version::new(1, 2, 0))
+        );
+        let tag = Tag::parse("api-v2@0.3.1-rc.1").unwrap();
+        assert_eq!(tag.prefix, "api-v2@");
+        assert_eq!(tag.version, version("0.3.1-rc.1"));
+        assert_eq!(Tag::parse("release-candidate").map(|tag| tag.name), None);
+        assert_eq!(Tag::new("v", Version::new(1, 0, 0)).name, "v1.0.0");
+    }
+
+    #[test]
+    fn paths_belong_to_the_deepest_package() {
+        let package = |name: &str, dir: &str| Package {
+            name: name.to_string(),
+            kind: crate::manifest::PackageKind::Cargo,
+            dir: dir.into(),
+            manifest: format!("{dir}/Cargo.toml").into(),
+            version: None,
+            dependencies: Vec::new(),
+        };
+        let packages = [
+            package("root", ""),
+            package("cli", "crates/cli"),
+            package("cli-macros", "crates/cli/macros"),
+        ];
+        let owner = |path: &str| owner(&packages, path).map(|p| p.name.as_str());
+        assert_eq!(owner("crates/cli/macros/src/lib.rs"), Some("cli-macros"));
+        assert_eq!(owner("crates/cli/src/main.rs"), Some("cli"));
+        assert_eq!(owner("crates/client/src/lib.rs"), Some("root"));
+        assert_eq!(owner("README.md"), Some("root"));
+    }
+}